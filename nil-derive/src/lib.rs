@@ -0,0 +1,327 @@
+//! Derive macros for [nil](https://docs.rs/nil), use them through its re-export instead of depending on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::parse::ParseStream;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Fields, Ident, LitStr, Type};
+
+/// Implements [Display](std::fmt::Display), [Error](std::error::Error) and optionally [From] for an error struct or enum.
+///
+/// - `#[error("...")]` on the struct or on each variant sets the [Display](std::fmt::Display) message.
+///   Fields can be used in the message by name, or by index (`{0}`) for tuple fields, extra format arguments can be passed after the message.
+/// - `#[error(transparent)]` forwards both [Display](std::fmt::Display) and [source](std::error::Error::source) to the single field.
+/// - `#[source]` marks the field returned by [source](std::error::Error::source). A field named `source` is used automatically.
+/// - `#[from]` implements [From] for the field's type, and marks it as the source. The struct or variant may only have that one field.
+///
+/// Because the source is kept rather than formatted into the message, errors that had messages added through `io_add_msg!` or `IoResultExt`
+/// still show every message as its own level when printed with `nil::report::Report`.
+///
+/// # Examples
+/// ```
+/// use nil::prelude::*;
+/// use std::io;
+///
+/// #[derive(Debug, NilError)]
+/// enum LevelError {
+///     #[error("failed to load level")]
+///     Io(#[from] io::Error),
+///     #[error("unknown tile {0:?} at {x}, {y}", x = .1, y = .2)]
+///     UnknownTile(char, usize, usize),
+///     #[error("level {name:?} has no spawn point")]
+///     NoSpawn { name: String },
+/// }
+///
+/// fn load() -> Result<(), LevelError> {
+///     let result: io::Result<()> = Err(io::Error::new(io::ErrorKind::NotFound, "no such file"));
+///     result.map_err(io_add_msg!("reading \"level.map\""))?;
+///     Ok(())
+/// }
+///
+/// let report = Report::new(load().unwrap_err());
+/// assert!(format!("{report:?}").starts_with("failed to load level\n\nCaused by:\n    0: reading \"level.map\"\n    1: no such file"));
+///
+/// assert_eq!(LevelError::UnknownTile('?', 4, 2).to_string(), "unknown tile '?' at 4, 2");
+/// assert_eq!(LevelError::NoSpawn { name: "intro".into() }.to_string(), "level \"intro\" has no spawn point");
+/// ```
+#[proc_macro_derive(NilError, attributes(error, from, source))]
+pub fn derive_nil_error(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	expand_nil_error(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+enum Message {
+	Format { fmt: LitStr, args: TokenStream2 },
+	Transparent,
+}
+
+/// A struct, or a single variant of an enum.
+struct Case {
+	/// `Self` or `Self::Variant`.
+	path: TokenStream2,
+	fields: Fields,
+	message: Message,
+	source: Option<usize>,
+	from: Option<usize>,
+}
+
+impl Case {
+	fn new(path: TokenStream2, fields: Fields, attrs: &[Attribute], span: proc_macro2::Span) -> syn::Result<Self> {
+		let message = parse_message(attrs)?.ok_or_else(|| syn::Error::new(span, "missing #[error(...)] attribute"))?;
+		let mut source = None;
+		let mut from = None;
+
+		for (i, field) in fields.iter().enumerate() {
+			let is_from = has_attr(&field.attrs, "from");
+
+			if is_from {
+				if fields.len() != 1 {
+					return Err(syn::Error::new(field.span(), "#[from] requires the field to be the only one"));
+				}
+				from = Some(i);
+			}
+
+			if is_from || has_attr(&field.attrs, "source") || field.ident.as_ref().is_some_and(|ident| ident == "source") {
+				if source.is_some_and(|source| source != i) {
+					return Err(syn::Error::new(field.span(), "only one field can be the source"));
+				}
+				source = Some(i);
+			}
+		}
+
+		if matches!(message, Message::Transparent) && fields.len() != 1 {
+			return Err(syn::Error::new(span, "#[error(transparent)] requires exactly one field"));
+		}
+
+		Ok(Self { path, fields, message, source, from })
+	}
+
+	fn binding(&self, i: usize) -> Ident {
+		match &self.fields {
+			Fields::Named(fields) => fields.named[i].ident.clone().unwrap(),
+			_ => format_ident!("_{i}"),
+		}
+	}
+
+	/// Pattern binding every field by reference.
+	fn pattern(&self) -> TokenStream2 {
+		let path = &self.path;
+		let bindings = (0..self.fields.len()).map(|i| self.binding(i));
+
+		match &self.fields {
+			Fields::Named(_) => quote! { #path { #(#bindings),* } },
+			Fields::Unnamed(_) => quote! { #path ( #(#bindings),* ) },
+			Fields::Unit => quote! { #path },
+		}
+	}
+
+	fn display_arm(&self) -> TokenStream2 {
+		let pattern = self.pattern();
+
+		match &self.message {
+			Message::Format { fmt, args } => {
+				let fmt = match self.fields {
+					Fields::Unnamed(_) => rewrite_positional(fmt),
+					_ => fmt.clone(),
+				};
+				let args = rewrite_member_args(args);
+				quote! { #pattern => ::core::write!(__formatter, #fmt #args), }
+			}
+			Message::Transparent => {
+				let binding = self.binding(0);
+				quote! { #pattern => ::core::fmt::Display::fmt(#binding, __formatter), }
+			}
+		}
+	}
+
+	fn source_arm(&self) -> TokenStream2 {
+		let pattern = self.pattern();
+
+		if let Message::Transparent = self.message {
+			let binding = self.binding(0);
+			return quote! { #pattern => ::core::error::Error::source((*#binding).__nil_as_dyn_error()), };
+		}
+
+		match self.source {
+			Some(i) => {
+				let binding = self.binding(i);
+				quote! { #pattern => ::core::option::Option::Some((*#binding).__nil_as_dyn_error()), }
+			}
+			None => quote! { #pattern => ::core::option::Option::None, },
+		}
+	}
+
+	fn impl_from(&self, input: &DeriveInput) -> Option<TokenStream2> {
+		let i = self.from?;
+		let ty: &Type = &self.fields.iter().nth(i)?.ty;
+		let name = &input.ident;
+		let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+		let path = &self.path;
+		let construct = match &self.fields {
+			Fields::Named(fields) => {
+				let ident = &fields.named[i].ident;
+				quote! { #path { #ident: source } }
+			}
+			_ => quote! { #path(source) },
+		};
+
+		Some(quote! {
+			impl #impl_generics ::core::convert::From<#ty> for #name #ty_generics #where_clause {
+				#[inline]
+				fn from(source: #ty) -> Self {
+					#construct
+				}
+			}
+		})
+	}
+}
+
+fn expand_nil_error(input: DeriveInput) -> syn::Result<TokenStream2> {
+	let cases = match &input.data {
+		Data::Struct(data) => vec![Case::new(quote!(Self), data.fields.clone(), &input.attrs, input.ident.span())?],
+		Data::Enum(data) => data
+			.variants
+			.iter()
+			.map(|variant| {
+				let ident = &variant.ident;
+				Case::new(quote!(Self::#ident), variant.fields.clone(), &variant.attrs, variant.span())
+			})
+			.collect::<syn::Result<_>>()?,
+		Data::Union(_) => return Err(syn::Error::new(input.ident.span(), "NilError can't be derived for unions")),
+	};
+
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	let display_arms = cases.iter().map(Case::display_arm);
+	let source_arms = cases.iter().map(Case::source_arm);
+	let from_impls = cases.iter().filter_map(|case| case.impl_from(&input));
+
+	// An empty enum can't be matched on with arms, but can still be dereferenced.
+	let empty = cases.is_empty().then(|| quote! { _ => match *self {} });
+
+	Ok(quote! {
+		impl #impl_generics ::core::fmt::Display for #name #ty_generics #where_clause {
+			#[allow(unused_variables)]
+			fn fmt(&self, __formatter: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+				match self {
+					#(#display_arms)*
+					#empty
+				}
+			}
+		}
+
+		impl #impl_generics ::core::error::Error for #name #ty_generics #where_clause {
+			#[allow(unused_variables)]
+			fn source(&self) -> ::core::option::Option<&(dyn ::core::error::Error + 'static)> {
+				// Lets sources be either sized errors or boxed trait objects.
+				trait __NilAsDynError {
+					fn __nil_as_dyn_error(&self) -> &(dyn ::core::error::Error + 'static);
+				}
+				impl<T: ::core::error::Error + 'static> __NilAsDynError for T {
+					fn __nil_as_dyn_error(&self) -> &(dyn ::core::error::Error + 'static) { self }
+				}
+				impl __NilAsDynError for dyn ::core::error::Error + 'static {
+					fn __nil_as_dyn_error(&self) -> &(dyn ::core::error::Error + 'static) { self }
+				}
+				impl __NilAsDynError for dyn ::core::error::Error + ::core::marker::Send + 'static {
+					fn __nil_as_dyn_error(&self) -> &(dyn ::core::error::Error + 'static) { self }
+				}
+				impl __NilAsDynError for dyn ::core::error::Error + ::core::marker::Send + ::core::marker::Sync + 'static {
+					fn __nil_as_dyn_error(&self) -> &(dyn ::core::error::Error + 'static) { self }
+				}
+
+				match self {
+					#(#source_arms)*
+					#empty
+				}
+			}
+		}
+
+		#(#from_impls)*
+	})
+}
+
+fn has_attr(attrs: &[Attribute], name: &str) -> bool {
+	attrs.iter().any(|attr| attr.path().is_ident(name))
+}
+
+fn parse_message(attrs: &[Attribute]) -> syn::Result<Option<Message>> {
+	let Some(attr) = attrs.iter().find(|attr| attr.path().is_ident("error")) else { return Ok(None) };
+
+	attr.parse_args_with(|input: ParseStream| {
+		if input.peek(Ident) {
+			let ident: Ident = input.parse()?;
+			if ident != "transparent" || !input.is_empty() {
+				return Err(syn::Error::new(ident.span(), "expected a format string or `transparent`"));
+			}
+			return Ok(Message::Transparent);
+		}
+
+		Ok(Message::Format { fmt: input.parse()?, args: input.parse()? })
+	})
+	.map(Some)
+}
+
+/// Turns `{0}` into `{_0}` so positional fields can be used in format strings through their bindings.
+fn rewrite_positional(fmt: &LitStr) -> LitStr {
+	let value = fmt.value();
+	let mut out = String::with_capacity(value.len());
+	let mut chars = value.chars().peekable();
+
+	while let Some(c) = chars.next() {
+		out.push(c);
+		if c != '{' {
+			continue;
+		}
+		match chars.peek() {
+			Some('{') => out.push(chars.next().unwrap()),
+			Some(c) if c.is_ascii_digit() => out.push('_'),
+			_ => {}
+		}
+	}
+
+	LitStr::new(&out, fmt.span())
+}
+
+/// Turns `.0` or `.name` in extra format arguments into the binding of that field.
+fn rewrite_member_args(args: &TokenStream2) -> TokenStream2 {
+	use proc_macro2::{Group, TokenTree};
+
+	let mut out = TokenStream2::new();
+	let mut tokens = args.clone().into_iter().peekable();
+	let mut prev_is_separator = true;
+
+	while let Some(token) = tokens.next() {
+		match &token {
+			TokenTree::Punct(punct) if punct.as_char() == '.' && prev_is_separator => match tokens.peek() {
+				Some(TokenTree::Literal(lit)) => {
+					out.extend([TokenTree::Ident(format_ident!("_{}", lit.to_string()))]);
+					tokens.next();
+					prev_is_separator = false;
+					continue;
+				}
+				Some(TokenTree::Ident(ident)) => {
+					out.extend([TokenTree::Ident(ident.clone())]);
+					tokens.next();
+					prev_is_separator = false;
+					continue;
+				}
+				_ => {}
+			},
+			TokenTree::Group(group) => {
+				let mut new = Group::new(group.delimiter(), rewrite_member_args(&group.stream()));
+				new.set_span(group.span());
+				out.extend([TokenTree::Group(new)]);
+				prev_is_separator = false;
+				continue;
+			}
+			_ => {}
+		}
+
+		prev_is_separator = matches!(&token, TokenTree::Punct(punct) if !matches!(punct.as_char(), '.' | '?'));
+		out.extend([token]);
+	}
+
+	out
+}
@@ -0,0 +1,251 @@
+//! Strongly typed angles, so radians and degrees can't be mixed up.
+//!
+//! # Examples
+//! ```
+//! use nil::prelude::*;
+//! use nil::math_prelude::*;
+//!
+//! let right = Degrees(90.0);
+//! let radians: Radians = right.into();
+//! assert!(approx_eq(radians.0, FRAC_PI_2));
+//!
+//! assert!(approx_eq(Degrees(-90.0).normalized().0, 270.0));
+//! assert!(approx_eq(Degrees(270.0).normalized_signed().0, -90.0));
+//!
+//! // Interpolation takes the shortest way around, here through 0 instead of 180.
+//! assert!(approx_eq(Degrees(350.0).lerp(Degrees(30.0), 0.5).normalized().0, 10.0));
+//!
+//! assert!(approx_eq(right.sin(), 1.0));
+//! assert_eq!(format!("{:.1}", Radians(PI_F64)), "3.1 rad");
+//! assert_eq!(format!("{}", right * 2.0), "180°");
+//! ```
+
+use core::fmt;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::math::{wrap_signed, wrap_unsigned, Float};
+
+/// An angle in radians.
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+#[repr(transparent)]
+pub struct Radians<T = f32>(pub T);
+
+/// An angle in degrees.
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+#[repr(transparent)]
+pub struct Degrees<T = f32>(pub T);
+
+impl<T: Float> Radians<T> {
+	pub const ZERO: Self = Self(T::ZERO);
+
+	#[inline]
+	fn half_turn() -> T {
+		T::PI
+	}
+
+	#[inline]
+	pub fn to_degrees(self) -> Degrees<T> {
+		Degrees(self.0.to_degrees())
+	}
+
+	/// Returns the angle of the vector (`x`, `y`) from the positive x axis.
+	#[cfg(feature = "std")]
+	#[inline]
+	pub fn atan2(y: T, x: T) -> Self {
+		Self(y.atan2(x))
+	}
+
+	#[cfg(feature = "std")]
+	#[inline]
+	pub fn sin(self) -> T {
+		self.0.sin()
+	}
+
+	#[cfg(feature = "std")]
+	#[inline]
+	pub fn cos(self) -> T {
+		self.0.cos()
+	}
+
+	#[cfg(feature = "std")]
+	#[inline]
+	pub fn tan(self) -> T {
+		self.0.tan()
+	}
+
+	#[cfg(feature = "std")]
+	#[inline]
+	pub fn sin_cos(self) -> (T, T) {
+		(self.0.sin(), self.0.cos())
+	}
+}
+
+impl<T: Float> Degrees<T> {
+	pub const ZERO: Self = Self(T::ZERO);
+
+	#[inline]
+	fn half_turn() -> T {
+		T::from_f64(180.)
+	}
+
+	#[inline]
+	pub fn to_radians(self) -> Radians<T> {
+		Radians(self.0.to_radians())
+	}
+
+	#[cfg(feature = "std")]
+	#[inline]
+	pub fn sin(self) -> T {
+		self.to_radians().sin()
+	}
+
+	#[cfg(feature = "std")]
+	#[inline]
+	pub fn cos(self) -> T {
+		self.to_radians().cos()
+	}
+
+	#[cfg(feature = "std")]
+	#[inline]
+	pub fn tan(self) -> T {
+		self.to_radians().tan()
+	}
+
+	#[cfg(feature = "std")]
+	#[inline]
+	pub fn sin_cos(self) -> (T, T) {
+		self.to_radians().sin_cos()
+	}
+}
+
+macro_rules! impl_angle {
+	($($angle:ident $unit:literal),*) => {$(
+		impl<T: Float> $angle<T> {
+			/// Wraps the angle to the range `[0, full turn)`.
+			#[inline]
+			pub fn normalized(self) -> Self {
+				Self(wrap_unsigned(self.0, Self::half_turn() + Self::half_turn()))
+			}
+
+			/// Wraps the angle to the range `(-half turn, half turn]`.
+			#[inline]
+			pub fn normalized_signed(self) -> Self {
+				Self(wrap_signed(self.0, Self::half_turn()))
+			}
+
+			/// Returns the shortest signed angle that gets from `self` to `other`.
+			#[inline]
+			pub fn angle_to(self, other: Self) -> Self {
+				(other - self).normalized_signed()
+			}
+
+			/// Interpolates from `self` to `other` by `t`, going the shortest way around.
+			///
+			/// The result isn't normalized, use [normalized](Self::normalized) if you need it to be.
+			#[inline]
+			pub fn lerp(self, other: Self, t: T) -> Self {
+				self + self.angle_to(other) * t
+			}
+		}
+
+		impl<T: Float> Add for $angle<T> {
+			type Output = Self;
+			#[inline]
+			fn add(self, rhs: Self) -> Self {
+				Self(self.0 + rhs.0)
+			}
+		}
+
+		impl<T: Float> Sub for $angle<T> {
+			type Output = Self;
+			#[inline]
+			fn sub(self, rhs: Self) -> Self {
+				Self(self.0 - rhs.0)
+			}
+		}
+
+		impl<T: Float> Neg for $angle<T> {
+			type Output = Self;
+			#[inline]
+			fn neg(self) -> Self {
+				Self(-self.0)
+			}
+		}
+
+		impl<T: Float> Mul<T> for $angle<T> {
+			type Output = Self;
+			#[inline]
+			fn mul(self, rhs: T) -> Self {
+				Self(self.0 * rhs)
+			}
+		}
+
+		impl<T: Float> Div<T> for $angle<T> {
+			type Output = Self;
+			#[inline]
+			fn div(self, rhs: T) -> Self {
+				Self(self.0 / rhs)
+			}
+		}
+
+		/// Ratio between two angles.
+		impl<T: Float> Div for $angle<T> {
+			type Output = T;
+			#[inline]
+			fn div(self, rhs: Self) -> T {
+				self.0 / rhs.0
+			}
+		}
+
+		impl<T: Float> AddAssign for $angle<T> {
+			#[inline]
+			fn add_assign(&mut self, rhs: Self) {
+				*self = *self + rhs;
+			}
+		}
+
+		impl<T: Float> SubAssign for $angle<T> {
+			#[inline]
+			fn sub_assign(&mut self, rhs: Self) {
+				*self = *self - rhs;
+			}
+		}
+
+		impl<T: Float> MulAssign<T> for $angle<T> {
+			#[inline]
+			fn mul_assign(&mut self, rhs: T) {
+				*self = *self * rhs;
+			}
+		}
+
+		impl<T: Float> DivAssign<T> for $angle<T> {
+			#[inline]
+			fn div_assign(&mut self, rhs: T) {
+				*self = *self / rhs;
+			}
+		}
+
+		impl<T: Float> fmt::Display for $angle<T> {
+			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				fmt::Display::fmt(&self.0, f)?;
+				f.write_str($unit)
+			}
+		}
+	)*};
+}
+
+impl_angle!(Radians " rad", Degrees "°");
+
+impl<T: Float> From<Degrees<T>> for Radians<T> {
+	#[inline]
+	fn from(value: Degrees<T>) -> Self {
+		value.to_radians()
+	}
+}
+
+impl<T: Float> From<Radians<T>> for Degrees<T> {
+	#[inline]
+	fn from(value: Radians<T>) -> Self {
+		value.to_degrees()
+	}
+}
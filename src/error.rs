@@ -0,0 +1,145 @@
+//! Utilities for adding context to errors without losing the original error.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+/// An error with a message describing what was being done when `source` occurred.
+///
+/// Displayed as the message followed by the source, separated by `: `, so nothing is lost when it's printed.
+/// The message on its own is available through [msg](Self::msg), and the source through [Error::source].
+#[derive(Debug)]
+pub struct ContextError {
+	msg: String,
+	source: Box<dyn Error + Send + Sync>,
+}
+
+impl ContextError {
+	pub fn new(msg: impl Into<String>, source: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+		Self { msg: msg.into(), source: source.into() }
+	}
+
+	/// The message added to the source error.
+	#[inline]
+	pub fn msg(&self) -> &str {
+		&self.msg
+	}
+
+	/// Discards the message, returning the error it was added to.
+	#[inline]
+	pub fn into_source(self) -> Box<dyn Error + Send + Sync> {
+		self.source
+	}
+}
+
+impl fmt::Display for ContextError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}: {}", self.msg, self.source)
+	}
+}
+
+impl Error for ContextError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		Some(&*self.source)
+	}
+}
+
+/// Iterator over an error and all of its [sources](Error::source), starting with the error itself.
+///
+/// # Examples
+/// ```
+/// use nil::error::{Chain, ContextError};
+/// use std::io;
+///
+/// let err = ContextError::new("loading level", ContextError::new("reading \"level.map\"", io::Error::other("disk on fire")));
+/// let messages: Vec<String> = Chain::new(&err).map(|err| err.to_string()).collect();
+///
+/// assert_eq!(messages, ["loading level: reading \"level.map\": disk on fire", "reading \"level.map\": disk on fire", "disk on fire"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Chain<'a> {
+	next: Option<&'a (dyn Error + 'static)>,
+}
+
+impl<'a> Chain<'a> {
+	#[inline]
+	pub fn new(err: &'a (dyn Error + 'static)) -> Self {
+		Self { next: Some(err) }
+	}
+}
+
+impl<'a> Iterator for Chain<'a> {
+	type Item = &'a (dyn Error + 'static);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let err = self.next?;
+		self.next = err.source();
+		Some(err)
+	}
+}
+
+/// Extension trait for adding context to errors, the method-style successor to [io_add_msg!](crate::io_add_msg).
+///
+/// The resulting [io::Error] has the same [io::ErrorKind] as the original error (or [io::ErrorKind::Other] if it wasn't an [io::Error]),
+/// and keeps the original error as its [source](Error::source).
+///
+/// # Examples
+/// ```
+/// use nil::prelude::*;
+/// use std::error::Error;
+/// use std::io;
+///
+/// let result: io::Result<()> = Err(io::Error::new(io::ErrorKind::NotFound, "no such file"));
+/// let err = result.context("loading settings").unwrap_err();
+///
+/// assert_eq!(err.kind(), io::ErrorKind::NotFound);
+/// assert_eq!(err.to_string(), "loading settings: no such file");
+/// assert_eq!(err.source().unwrap().to_string(), "no such file");
+/// ```
+pub trait IoResultExt<T> {
+	/// Adds `msg` to the error, if there is one.
+	fn context(self, msg: impl Into<String>) -> io::Result<T>;
+
+	/// Adds the message returned by `f` to the error, if there is one. `f` is only called if there is an error.
+	fn with_context<M: Into<String>>(self, f: impl FnOnce() -> M) -> io::Result<T>;
+
+	/// Adds `path` to the error, if there is one.
+	fn with_path(self, path: impl AsRef<Path>) -> io::Result<T>;
+}
+
+impl<T, E: Error + Send + Sync + 'static> IoResultExt<T> for Result<T, E> {
+	#[inline]
+	fn context(self, msg: impl Into<String>) -> io::Result<T> {
+		self.map_err(|err| add_context(err, msg.into()))
+	}
+
+	#[inline]
+	fn with_context<M: Into<String>>(self, f: impl FnOnce() -> M) -> io::Result<T> {
+		self.map_err(|err| add_context(err, f().into()))
+	}
+
+	#[inline]
+	fn with_path(self, path: impl AsRef<Path>) -> io::Result<T> {
+		self.map_err(|err| add_context(err, format!("{:?}", path.as_ref())))
+	}
+}
+
+/// The message `err` adds on top of its source, which is just [ContextError::msg] if it's a [ContextError], or one wrapped in an [io::Error].
+pub(crate) fn own_msg(err: &(dyn Error + 'static)) -> String {
+	let context = err
+		.downcast_ref::<ContextError>()
+		.or_else(|| err.downcast_ref::<io::Error>()?.get_ref()?.downcast_ref::<ContextError>());
+
+	match context {
+		Some(context) => context.msg.clone(),
+		None => err.to_string(),
+	}
+}
+
+fn add_context(err: impl Error + Send + Sync + 'static, msg: String) -> io::Error {
+	let source: Box<dyn Error + Send + Sync> = Box::new(err);
+	let kind = source.downcast_ref::<io::Error>().map_or(io::ErrorKind::Other, io::Error::kind);
+
+	io::Error::new(kind, ContextError::new(msg, source))
+}
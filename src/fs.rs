@@ -0,0 +1,149 @@
+//! Drop-in replacement for [std::fs] whose functions include the operation and path(s) involved in their errors.
+//!
+//! The [io::ErrorKind] of the original error is kept, so matching on it works the same as with [std::fs].
+//!
+//! # Examples
+//! ```
+//! use nil::fs;
+//!
+//! let err = fs::read_to_string("this/file/does/not/exist.txt").unwrap_err();
+//!
+//! assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+//! assert!(err.to_string().contains("this/file/does/not/exist.txt"));
+//! ```
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::io_add_msg;
+
+pub use std::fs::{DirBuilder, DirEntry, File, FileTimes, FileType, Metadata, OpenOptions, Permissions, ReadDir};
+
+crate::flat! {
+	atomic;
+	walk;
+	dir;
+	temp;
+}
+
+/// Path-aware version of [std::fs::canonicalize].
+pub fn canonicalize(path: impl AsRef<Path>) -> io::Result<PathBuf> {
+	let path = path.as_ref();
+	std::fs::canonicalize(path).map_err(io_add_msg!("failed to canonicalize {path:?}"))
+}
+
+/// Path-aware version of [std::fs::copy].
+pub fn copy(from: impl AsRef<Path>, to: impl AsRef<Path>) -> io::Result<u64> {
+	let (from, to) = (from.as_ref(), to.as_ref());
+	std::fs::copy(from, to).map_err(io_add_msg!("failed to copy {from:?} to {to:?}"))
+}
+
+/// Path-aware version of [std::fs::create_dir].
+pub fn create_dir(path: impl AsRef<Path>) -> io::Result<()> {
+	let path = path.as_ref();
+	std::fs::create_dir(path).map_err(io_add_msg!("failed to create directory {path:?}"))
+}
+
+/// Path-aware version of [std::fs::create_dir_all].
+pub fn create_dir_all(path: impl AsRef<Path>) -> io::Result<()> {
+	let path = path.as_ref();
+	std::fs::create_dir_all(path).map_err(io_add_msg!("failed to create directory {path:?}"))
+}
+
+/// Path-aware version of [std::fs::exists].
+pub fn exists(path: impl AsRef<Path>) -> io::Result<bool> {
+	let path = path.as_ref();
+	std::fs::exists(path).map_err(io_add_msg!("failed to check if {path:?} exists"))
+}
+
+/// Path-aware version of [std::fs::hard_link].
+pub fn hard_link(original: impl AsRef<Path>, link: impl AsRef<Path>) -> io::Result<()> {
+	let (original, link) = (original.as_ref(), link.as_ref());
+	std::fs::hard_link(original, link).map_err(io_add_msg!("failed to hard link {link:?} to {original:?}"))
+}
+
+/// Path-aware version of [std::fs::metadata].
+pub fn metadata(path: impl AsRef<Path>) -> io::Result<Metadata> {
+	let path = path.as_ref();
+	std::fs::metadata(path).map_err(io_add_msg!("failed to read metadata of {path:?}"))
+}
+
+/// Path-aware version of [std::fs::read].
+pub fn read(path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+	let path = path.as_ref();
+	std::fs::read(path).map_err(io_add_msg!("failed to read {path:?}"))
+}
+
+/// Path-aware version of [std::fs::read_dir].
+///
+/// NOTE: Only errors from opening the directory have the path attached, not ones from iterating the returned [ReadDir].
+pub fn read_dir(path: impl AsRef<Path>) -> io::Result<ReadDir> {
+	let path = path.as_ref();
+	std::fs::read_dir(path).map_err(io_add_msg!("failed to read directory {path:?}"))
+}
+
+/// Path-aware version of [std::fs::read_link].
+pub fn read_link(path: impl AsRef<Path>) -> io::Result<PathBuf> {
+	let path = path.as_ref();
+	std::fs::read_link(path).map_err(io_add_msg!("failed to read link {path:?}"))
+}
+
+/// Path-aware version of [std::fs::read_to_string].
+pub fn read_to_string(path: impl AsRef<Path>) -> io::Result<String> {
+	let path = path.as_ref();
+	std::fs::read_to_string(path).map_err(io_add_msg!("failed to read {path:?}"))
+}
+
+/// Path-aware version of [std::fs::remove_dir].
+pub fn remove_dir(path: impl AsRef<Path>) -> io::Result<()> {
+	let path = path.as_ref();
+	std::fs::remove_dir(path).map_err(io_add_msg!("failed to remove directory {path:?}"))
+}
+
+/// Path-aware version of [std::fs::remove_dir_all].
+pub fn remove_dir_all(path: impl AsRef<Path>) -> io::Result<()> {
+	let path = path.as_ref();
+	std::fs::remove_dir_all(path).map_err(io_add_msg!("failed to remove directory {path:?}"))
+}
+
+/// Path-aware version of [std::fs::remove_file].
+pub fn remove_file(path: impl AsRef<Path>) -> io::Result<()> {
+	let path = path.as_ref();
+	std::fs::remove_file(path).map_err(io_add_msg!("failed to remove file {path:?}"))
+}
+
+/// Path-aware version of [std::fs::rename].
+pub fn rename(from: impl AsRef<Path>, to: impl AsRef<Path>) -> io::Result<()> {
+	let (from, to) = (from.as_ref(), to.as_ref());
+	std::fs::rename(from, to).map_err(io_add_msg!("failed to rename {from:?} to {to:?}"))
+}
+
+/// Path-aware version of [std::fs::set_permissions].
+pub fn set_permissions(path: impl AsRef<Path>, perm: Permissions) -> io::Result<()> {
+	let path = path.as_ref();
+	std::fs::set_permissions(path, perm).map_err(io_add_msg!("failed to set permissions of {path:?}"))
+}
+
+/// Path-aware version of [std::fs::symlink_metadata].
+pub fn symlink_metadata(path: impl AsRef<Path>) -> io::Result<Metadata> {
+	let path = path.as_ref();
+	std::fs::symlink_metadata(path).map_err(io_add_msg!("failed to read metadata of {path:?}"))
+}
+
+/// Path-aware version of [std::fs::write].
+pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+	let path = path.as_ref();
+	std::fs::write(path, contents).map_err(io_add_msg!("failed to write {path:?}"))
+}
+
+/// Path-aware version of [File::open].
+pub fn open(path: impl AsRef<Path>) -> io::Result<File> {
+	let path = path.as_ref();
+	File::open(path).map_err(io_add_msg!("failed to open {path:?}"))
+}
+
+/// Path-aware version of [File::create].
+pub fn create(path: impl AsRef<Path>) -> io::Result<File> {
+	let path = path.as_ref();
+	File::create(path).map_err(io_add_msg!("failed to create {path:?}"))
+}
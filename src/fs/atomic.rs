@@ -0,0 +1,240 @@
+use std::ffi::{OsStr, OsString};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::io_add_msg;
+
+use super::{File, OpenOptions};
+
+/// Writes `contents` to `path` through an [AtomicFile], so `path` either keeps its old contents or has all of the new ones, even if the program crashes.
+///
+/// # Examples
+/// ```
+/// use nil::fs::{self, TempDir};
+///
+/// let dir = TempDir::new().unwrap();
+/// let path = dir.path().join("settings.toml");
+/// fs::write_atomic(&path, "volume = 0.5\n").unwrap();
+/// assert_eq!(fs::read_to_string(&path).unwrap(), "volume = 0.5\n");
+/// ```
+pub fn write_atomic(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+	let mut file = AtomicFile::create(path)?;
+	file.write_all(contents.as_ref())?;
+	file.commit()
+}
+
+/// A writer to a temporary file next to `path`, which replaces `path` when [committed](Self::commit), or is removed if dropped before then.
+///
+/// Committing syncs the temporary file to disk before renaming it over `path`, so a crash leaves either the old or the new contents, never a mix.
+/// If `path` already exists, its permissions are kept.
+///
+/// # Examples
+/// ```
+/// use nil::fs::{self, AtomicFile, TempDir};
+/// use std::io::Write;
+///
+/// let dir = TempDir::new().unwrap();
+/// let path = dir.path().join("slot1.sav");
+/// fs::write(&path, "level 1").unwrap();
+///
+/// let mut file = AtomicFile::create(&path).unwrap().with_backups(2);
+/// write!(file, "level 2").unwrap();
+/// // Nothing changes until it's committed.
+/// assert_eq!(fs::read_to_string(&path).unwrap(), "level 1");
+/// file.commit().unwrap();
+///
+/// let mut file = AtomicFile::create(&path).unwrap().with_backups(2);
+/// write!(file, "level 3").unwrap();
+/// file.commit().unwrap();
+///
+/// let mut file = AtomicFile::create(&path).unwrap();
+/// write!(file, "level 99").unwrap();
+/// // Dropping it without committing throws the new contents away.
+/// drop(file);
+///
+/// let backup = |suffix: &str| fs::read_to_string(path.with_extension(format!("sav.{suffix}"))).unwrap();
+/// assert_eq!(fs::read_to_string(&path).unwrap(), "level 3");
+/// assert_eq!(backup("bak"), "level 2");
+/// assert_eq!(backup("bak.1"), "level 1");
+/// ```
+#[derive(Debug)]
+pub struct AtomicFile {
+	path: PathBuf,
+	temp_path: PathBuf,
+	/// [None] once committed.
+	file: Option<File>,
+	backups: usize,
+}
+
+impl AtomicFile {
+	/// Creates a temporary file in the same directory as `path` to write to.
+	pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+		let path = path.as_ref().to_path_buf();
+		let (file, temp_path) = create_unique_file(parent_dir(&path), &temp_prefix(&path), ".tmp", false)
+			.map_err(io_add_msg!("failed to create a temporary file for {path:?}"))?;
+
+		if let Ok(metadata) = std::fs::metadata(&path) {
+			file.set_permissions(metadata.permissions()).map_err(io_add_msg!("failed to set permissions of {temp_path:?}"))?;
+		}
+
+		Ok(Self { path, temp_path, file: Some(file), backups: 0 })
+	}
+
+	/// Keeps up to `count` old versions of `path` when committing, the newest as `path.bak`, and older ones as `path.bak.1`, `path.bak.2`, ...
+	pub fn with_backups(mut self, count: usize) -> Self {
+		self.backups = count;
+		self
+	}
+
+	/// The path that will be replaced when committing.
+	#[inline]
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// Syncs the written contents to disk and replaces `path` with them.
+	pub fn commit(mut self) -> io::Result<()> {
+		let Some(file) = self.file.take() else { return Ok(()) };
+		let (path, temp_path) = (&self.path, &self.temp_path);
+
+		let result = (|| {
+			file.sync_all().map_err(io_add_msg!("failed to sync {temp_path:?}"))?;
+			drop(file);
+
+			if self.backups > 0 && path.exists() {
+				rotate_backups(path, self.backups)?;
+			}
+
+			super::rename(temp_path, path)?;
+			sync_dir(parent_dir(path));
+			Ok(())
+		})();
+
+		if result.is_err() {
+			let _ = std::fs::remove_file(temp_path);
+		}
+		result
+	}
+}
+
+impl Write for AtomicFile {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		let (file, path) = self.parts();
+		file.write(buf).map_err(io_add_msg!("failed to write {path:?}"))
+	}
+
+	fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+		let (file, path) = self.parts();
+		file.write_all(buf).map_err(io_add_msg!("failed to write {path:?}"))
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		let (file, path) = self.parts();
+		file.flush().map_err(io_add_msg!("failed to flush {path:?}"))
+	}
+}
+
+impl AtomicFile {
+	#[inline]
+	fn parts(&mut self) -> (&mut File, &Path) {
+		// Only taken out by `commit`, which consumes self.
+		(self.file.as_mut().unwrap(), &self.path)
+	}
+}
+
+impl Drop for AtomicFile {
+	fn drop(&mut self) {
+		if self.file.take().is_some() {
+			let _ = std::fs::remove_file(&self.temp_path);
+		}
+	}
+}
+
+/// Shifts `path.bak.N` backups up by one, dropping the oldest, then links or copies `path` to `path.bak`.
+fn rotate_backups(path: &Path, count: usize) -> io::Result<()> {
+	let oldest = backup_path(path, count - 1);
+	if oldest.exists() {
+		super::remove_file(&oldest)?;
+	}
+	for i in (0..count - 1).rev() {
+		let from = backup_path(path, i);
+		if from.exists() {
+			super::rename(&from, backup_path(path, i + 1))?;
+		}
+	}
+
+	let backup = backup_path(path, 0);
+	// Hard linking keeps `path` in place until it's replaced, and is cheaper than copying.
+	if std::fs::hard_link(path, &backup).is_err() {
+		super::copy(path, &backup)?;
+	}
+	Ok(())
+}
+
+fn backup_path(path: &Path, i: usize) -> PathBuf {
+	let mut name = OsString::from(path.as_os_str());
+	name.push(".bak");
+	if i > 0 {
+		name.push(format!(".{i}"));
+	}
+	PathBuf::from(name)
+}
+
+/// Like [Path::parent], but returns `.` instead of an empty path for relative paths with one component.
+pub(crate) fn parent_dir(path: &Path) -> &Path {
+	match path.parent() {
+		Some(parent) if !parent.as_os_str().is_empty() => parent,
+		_ => Path::new("."),
+	}
+}
+
+/// Hidden file name prefix for temporary files of `path`.
+pub(crate) fn temp_prefix(path: &Path) -> OsString {
+	let mut prefix = OsString::from(".");
+	prefix.push(path.file_name().unwrap_or_default());
+	prefix.push(".");
+	prefix
+}
+
+/// Makes sure a rename in `dir` is on disk, only possible on unix.
+fn sync_dir(dir: &Path) {
+	#[cfg(unix)]
+	if let Ok(dir) = File::open(dir) {
+		let _ = dir.sync_all();
+	}
+	#[cfg(not(unix))]
+	let _ = dir;
+}
+
+/// Returns a path in `dir` named `prefix`, a part unique to this process and call, then `suffix`. Doesn't check if it exists.
+pub(crate) fn unique_path(dir: &Path, prefix: &OsStr, suffix: &str) -> PathBuf {
+	static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+	let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().subsec_nanos();
+	let mut name = prefix.to_os_string();
+	name.push(format!("{}-{}-{nanos:08x}{suffix}", std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed)));
+	dir.join(name)
+}
+
+/// Creates a new file at a [unique_path], trying again if it already exists.
+/// If `private`, only the current user can access it on unix, otherwise it gets the same permissions as any new file.
+pub(crate) fn create_unique_file(dir: &Path, prefix: &OsStr, suffix: &str, private: bool) -> io::Result<(File, PathBuf)> {
+	let mut options = OpenOptions::new();
+	options.read(true).write(true).create_new(true);
+	#[cfg(unix)]
+	if private {
+		std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+	}
+	#[cfg(not(unix))]
+	let _ = private;
+
+	loop {
+		let path = unique_path(dir, prefix, suffix);
+		match options.open(&path) {
+			Ok(file) => return Ok((file, path)),
+			Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+			Err(err) => return Err(err),
+		}
+	}
+}
@@ -0,0 +1,333 @@
+use std::io;
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::io_add_msg;
+
+use super::{File, Metadata, OpenOptions};
+
+/// What to do when something being copied or moved already exists at the destination.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Overwrite {
+	/// Fail with [io::ErrorKind::AlreadyExists].
+	#[default]
+	Error,
+	/// Leave the existing file alone.
+	Skip,
+	/// Replace the existing file.
+	Replace,
+}
+
+/// Progress of [copy_dir_all_with_progress] or [move_dir_with_progress]. Skipped files count as done.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CopyProgress {
+	pub files_done: u64,
+	pub files_total: u64,
+	pub files_skipped: u64,
+	pub bytes_done: u64,
+	pub bytes_total: u64,
+}
+
+/// Recursively copies the directory `from` to `to`, merging with `to` if it already exists.
+///
+/// Files that already exist in `to` are handled according to `overwrite`, with [Overwrite::Error] checking all of them before anything is copied. Permissions and modification times are preserved,
+/// and symbolic links are copied as links on unix, or as what they point to elsewhere.
+///
+/// # Examples
+/// ```
+/// use nil::fs::{self, Overwrite, TempDir};
+///
+/// let dir = TempDir::new().unwrap();
+/// let root = dir.path();
+/// fs::create_dir_all(root.join("saves/old")).unwrap();
+/// fs::write(root.join("saves/slot1.sav"), "slot 1").unwrap();
+/// fs::write(root.join("saves/old/slot1.sav"), "old slot 1").unwrap();
+///
+/// let progress = fs::copy_dir_all(root.join("saves"), root.join("backup"), Overwrite::Error).unwrap();
+/// assert_eq!((progress.files_done, progress.bytes_done), (2, 16));
+/// assert_eq!(fs::read_to_string(root.join("backup/old/slot1.sav")).unwrap(), "old slot 1");
+///
+/// fs::write(root.join("saves/slot1.sav"), "slot 1, but further").unwrap();
+/// fs::write(root.join("saves/slot2.sav"), "slot 2").unwrap();
+/// let err = fs::copy_dir_all(root.join("saves"), root.join("backup"), Overwrite::Error).unwrap_err();
+/// assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+/// // Nothing was copied, not even the files that didn't exist yet.
+/// assert!(!root.join("backup/slot2.sav").exists());
+///
+/// let progress = fs::copy_dir_all(root.join("saves"), root.join("backup"), Overwrite::Skip).unwrap();
+/// assert_eq!(progress.files_skipped, 2);
+/// assert_eq!(fs::read_to_string(root.join("backup/slot1.sav")).unwrap(), "slot 1");
+///
+/// fs::copy_dir_all(root.join("saves"), root.join("backup"), Overwrite::Replace).unwrap();
+/// assert_eq!(fs::read_to_string(root.join("backup/slot1.sav")).unwrap(), "slot 1, but further");
+/// ```
+pub fn copy_dir_all(from: impl AsRef<Path>, to: impl AsRef<Path>, overwrite: Overwrite) -> io::Result<CopyProgress> {
+	copy_dir_inner(from.as_ref(), to.as_ref(), overwrite, &mut |_| {})
+}
+
+/// [copy_dir_all], calling `progress` once before copying and after every file.
+///
+/// # Examples
+/// ```
+/// use nil::fs::{self, Overwrite, TempDir};
+/// use nil::human::HumanBytes;
+///
+/// let dir = TempDir::new().unwrap();
+/// let root = dir.path();
+/// fs::create_dir_all(root.join("assets")).unwrap();
+/// fs::write(root.join("assets/a.png"), [0; 1024]).unwrap();
+/// fs::write(root.join("assets/b.png"), [0; 2048]).unwrap();
+///
+/// let mut reports = Vec::new();
+/// fs::copy_dir_all_with_progress(root.join("assets"), root.join("build"), Overwrite::Error, |progress| {
+///     reports.push(format!("{}/{} files, {}/{}", progress.files_done, progress.files_total, HumanBytes::binary(progress.bytes_done), HumanBytes::binary(progress.bytes_total)));
+/// })
+/// .unwrap();
+/// assert_eq!(reports, ["0/2 files, 0B/3KiB", "1/2 files, 1KiB/3KiB", "2/2 files, 3KiB/3KiB"]);
+/// ```
+pub fn copy_dir_all_with_progress(
+	from: impl AsRef<Path>,
+	to: impl AsRef<Path>,
+	overwrite: Overwrite,
+	mut progress: impl FnMut(&CopyProgress),
+) -> io::Result<CopyProgress> {
+	copy_dir_inner(from.as_ref(), to.as_ref(), overwrite, &mut progress)
+}
+
+/// Moves the directory `from` to `to`, by renaming it if possible, or by copying and then removing it if they're on different devices.
+///
+/// `from` has to be a directory. If `to` already exists, [Overwrite::Skip] leaves both alone,
+/// and [Overwrite::Replace] moves `to` out of the way, only removing it once `from` has been moved in its place.
+///
+/// # Examples
+/// ```
+/// use nil::fs::{self, Overwrite, TempDir};
+///
+/// let dir = TempDir::new().unwrap();
+/// let root = dir.path();
+/// fs::create_dir_all(root.join("draft")).unwrap();
+/// fs::write(root.join("draft/level.map"), "###").unwrap();
+///
+/// fs::move_dir(root.join("draft"), root.join("published"), Overwrite::Error).unwrap();
+/// assert!(!root.join("draft").exists());
+/// assert_eq!(fs::read_to_string(root.join("published/level.map")).unwrap(), "###");
+///
+/// // Nothing is replaced if there's nothing to move.
+/// let err = fs::move_dir(root.join("draft"), root.join("published"), Overwrite::Replace).unwrap_err();
+/// assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+/// assert!(root.join("published/level.map").exists());
+/// ```
+pub fn move_dir(from: impl AsRef<Path>, to: impl AsRef<Path>, overwrite: Overwrite) -> io::Result<()> {
+	move_dir_inner(from.as_ref(), to.as_ref(), overwrite, &mut |_| {})
+}
+
+/// [move_dir], calling `progress` like [copy_dir_all_with_progress] if it has to copy. Not called at all if the directory could be renamed.
+pub fn move_dir_with_progress(
+	from: impl AsRef<Path>,
+	to: impl AsRef<Path>,
+	overwrite: Overwrite,
+	mut progress: impl FnMut(&CopyProgress),
+) -> io::Result<()> {
+	move_dir_inner(from.as_ref(), to.as_ref(), overwrite, &mut progress)
+}
+
+/// Removes everything inside of the directory at `path`, but not the directory itself.
+///
+/// # Examples
+/// ```
+/// use nil::fs::{self, TempDir};
+///
+/// let dir = TempDir::new().unwrap();
+/// let cache = dir.path();
+/// fs::create_dir(cache.join("shaders")).unwrap();
+/// fs::write(cache.join("shaders/lit.spv"), "").unwrap();
+/// fs::write(cache.join("index"), "").unwrap();
+///
+/// fs::remove_dir_contents(&cache).unwrap();
+/// assert_eq!(fs::read_dir(cache).unwrap().count(), 0);
+/// ```
+pub fn remove_dir_contents(path: impl AsRef<Path>) -> io::Result<()> {
+	let dir = path.as_ref();
+	for entry in super::read_dir(dir)? {
+		let entry = entry.map_err(io_add_msg!("failed to read directory {dir:?}"))?;
+		let path = entry.path();
+
+		if entry.file_type().map_err(io_add_msg!("failed to read metadata of {path:?}"))?.is_dir() {
+			super::remove_dir_all(&path)?;
+		} else {
+			super::remove_file(&path)?;
+		}
+	}
+
+	Ok(())
+}
+
+fn copy_dir_inner(from: &Path, to: &Path, overwrite: Overwrite, progress: &mut dyn FnMut(&CopyProgress)) -> io::Result<CopyProgress> {
+	let entries = super::walk(from).sort_by_file_name().collect::<io::Result<Vec<_>>>()?;
+	if !entries.first().is_some_and(|root| root.is_dir()) {
+		return Err(io::Error::from(io::ErrorKind::NotADirectory)).map_err(io_add_msg!("failed to copy {from:?} to {to:?}"));
+	}
+
+	let mut state = CopyProgress::default();
+	let mut files = Vec::new();
+	let mut dirs = Vec::new();
+
+	for entry in &entries {
+		let metadata = entry.metadata()?;
+
+		// Checked up front, so a conflict doesn't leave `to` with only some of the files.
+		if overwrite == Overwrite::Error {
+			let to_entry = to.join(entry.path().strip_prefix(from).unwrap());
+			if let Ok(to_metadata) = std::fs::symlink_metadata(&to_entry) {
+				if !(entry.is_dir() && to_metadata.is_dir()) {
+					let from_entry = entry.path();
+					return Err(io::Error::from(io::ErrorKind::AlreadyExists)).map_err(io_add_msg!("failed to copy {from_entry:?} to {to_entry:?}"));
+				}
+			}
+		}
+
+		if entry.is_dir() {
+			dirs.push((entry.path(), metadata));
+		} else {
+			state.files_total += 1;
+			state.bytes_total += metadata.len();
+			files.push((entry, metadata));
+		}
+	}
+
+	progress(&state);
+
+	for (from_dir, _) in &dirs {
+		let to_dir = to.join(from_dir.strip_prefix(from).unwrap());
+		super::create_dir_all(&to_dir)?;
+	}
+
+	for (entry, metadata) in files {
+		let from_file = entry.path();
+		let to_file = to.join(from_file.strip_prefix(from).unwrap());
+
+		if std::fs::symlink_metadata(&to_file).is_ok() {
+			match overwrite {
+				// Only if it appeared after the check above.
+				Overwrite::Error => {
+					return Err(io::Error::from(io::ErrorKind::AlreadyExists)).map_err(io_add_msg!("failed to copy {from_file:?} to {to_file:?}"));
+				}
+				Overwrite::Skip => {
+					state.files_done += 1;
+					state.files_skipped += 1;
+					state.bytes_done += metadata.len();
+					progress(&state);
+					continue;
+				}
+				// Removed rather than written over, so links at the destination get replaced instead of written through.
+				Overwrite::Replace => super::remove_file(&to_file)?,
+			}
+		}
+
+		if entry.is_symlink() {
+			copy_symlink(from_file, &to_file)?;
+		} else {
+			super::copy(from_file, &to_file)?;
+			copy_modified(from_file, &metadata, &to_file)?;
+		}
+
+		state.files_done += 1;
+		state.bytes_done += metadata.len();
+		progress(&state);
+	}
+
+	// Done last, as adding files changes the modification time of directories, and read only ones couldn't be added to.
+	for (from_dir, metadata) in dirs.iter().rev() {
+		let to_dir = to.join(from_dir.strip_prefix(from).unwrap());
+		copy_modified(from_dir, metadata, &to_dir)?;
+		super::set_permissions(&to_dir, metadata.permissions())?;
+	}
+
+	Ok(state)
+}
+
+fn move_dir_inner(from: &Path, to: &Path, overwrite: Overwrite, progress: &mut dyn FnMut(&CopyProgress)) -> io::Result<()> {
+	if !super::symlink_metadata(from)?.is_dir() {
+		return Err(io::Error::from(io::ErrorKind::NotADirectory)).map_err(io_add_msg!("failed to move {from:?} to {to:?}"));
+	}
+
+	// The old `to`, moved out of the way rather than removed, so it can be put back if moving `from` fails.
+	let mut replaced = None;
+	if let Ok(metadata) = std::fs::symlink_metadata(to) {
+		match overwrite {
+			Overwrite::Error => {
+				return Err(io::Error::from(io::ErrorKind::AlreadyExists)).map_err(io_add_msg!("failed to move {from:?} to {to:?}"));
+			}
+			Overwrite::Skip => return Ok(()),
+			Overwrite::Replace => {
+				let aside = super::unique_path(super::parent_dir(to), &super::temp_prefix(to), ".old");
+				super::rename(to, &aside)?;
+				replaced = Some((aside, metadata.is_dir()));
+			}
+		}
+	}
+
+	// Whether `from` was copied, and still has to be removed.
+	let copied = match std::fs::rename(from, to) {
+		Ok(()) => Ok(false),
+		Err(err) if err.kind() == io::ErrorKind::CrossesDevices => match copy_dir_inner(from, to, Overwrite::Error, progress) {
+			Ok(_) => Ok(true),
+			Err(err) => {
+				// Only holds what was copied so far, `from` is still intact.
+				let _ = std::fs::remove_dir_all(to);
+				Err(err)
+			}
+		},
+		Err(err) => Err(err).map_err(io_add_msg!("failed to move {from:?} to {to:?}")),
+	};
+
+	let removed_replaced = match (&replaced, &copied) {
+		(Some((aside, _)), Err(_)) => {
+			let _ = std::fs::rename(aside, to);
+			Ok(())
+		}
+		(Some((aside, true)), Ok(_)) => super::remove_dir_all(aside),
+		(Some((aside, false)), Ok(_)) => super::remove_file(aside),
+		(None, _) => Ok(()),
+	};
+
+	if copied? {
+		super::remove_dir_all(from)?;
+	}
+	removed_replaced
+}
+
+/// Sets the modification time of `to` to the one in `metadata`, which is of `from`.
+fn copy_modified(from: &Path, metadata: &Metadata, to: &Path) -> io::Result<()> {
+	let modified: SystemTime = metadata.modified().map_err(io_add_msg!("failed to read modification time of {from:?}"))?;
+	open_for_times(to)
+		.and_then(|file| file.set_modified(modified))
+		.map_err(io_add_msg!("failed to set modification time of {to:?}"))
+}
+
+/// Opens a file or directory with just enough access to change its times, which works even if it's read only.
+fn open_for_times(path: &Path) -> io::Result<File> {
+	let mut options = OpenOptions::new();
+	#[cfg(windows)]
+	{
+		use std::os::windows::fs::OpenOptionsExt;
+		const FILE_WRITE_ATTRIBUTES: u32 = 0x100;
+		// Needed to open directories.
+		const FILE_FLAG_BACKUP_SEMANTICS: u32 = 0x0200_0000;
+		options.access_mode(FILE_WRITE_ATTRIBUTES).custom_flags(FILE_FLAG_BACKUP_SEMANTICS);
+	}
+	#[cfg(not(windows))]
+	options.read(true);
+	options.open(path)
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+	let target = super::read_link(from)?;
+	std::os::unix::fs::symlink(&target, to).map_err(io_add_msg!("failed to create symbolic link {to:?} to {target:?}"))
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+	super::copy(from, to).map(drop)
+}
@@ -0,0 +1,245 @@
+use std::ffi::OsStr;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::io_add_msg;
+
+use super::File;
+
+const DEFAULT_PREFIX: &str = "nil-";
+
+/// A uniquely named directory in [env::temp_dir](std::env::temp_dir) that's removed along with its contents when dropped.
+/// On unix only the current user can access it.
+///
+/// # Examples
+/// ```
+/// use nil::prelude::*;
+/// use nil::fs::{self, TempDir};
+///
+/// let dir = TempDir::new().unwrap();
+/// fs::write(dir.path().join("config.toml"), "fullscreen = true").unwrap();
+///
+/// let path: String = dir.path().s();
+/// assert!(fs::exists(&path).unwrap());
+///
+/// drop(dir);
+/// assert!(!fs::exists(&path).unwrap());
+///
+/// // Use into_path to keep it around.
+/// let kept = TempDir::with_prefix("level-editor-").unwrap().into_path();
+/// assert!(kept.file_name().unwrap().s().starts_with("level-editor-"));
+/// assert!(kept.is_dir());
+/// # fs::remove_dir(kept).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct TempDir {
+	path: PathBuf,
+	keep: bool,
+}
+
+impl TempDir {
+	/// Creates a new directory in [env::temp_dir](std::env::temp_dir).
+	pub fn new() -> io::Result<Self> {
+		Self::with_prefix(DEFAULT_PREFIX)
+	}
+
+	/// Creates a new directory in [env::temp_dir](std::env::temp_dir), with a name starting with `prefix`.
+	pub fn with_prefix(prefix: impl AsRef<OsStr>) -> io::Result<Self> {
+		Self::new_in(std::env::temp_dir(), prefix)
+	}
+
+	/// Creates a new directory in `dir`, with a name starting with `prefix`.
+	pub fn new_in(dir: impl AsRef<Path>, prefix: impl AsRef<OsStr>) -> io::Result<Self> {
+		let dir = dir.as_ref();
+		loop {
+			let path = super::unique_path(dir, prefix.as_ref(), "");
+			match create_private_dir(&path) {
+				Ok(()) => return Ok(Self { path, keep: false }),
+				Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+				Err(err) => return Err(err).map_err(io_add_msg!("failed to create a temporary directory in {dir:?}")),
+			}
+		}
+	}
+
+	#[inline]
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// Stops the directory from being removed when dropped, useful to look at what's in it after a test fails.
+	#[inline]
+	pub fn keep(&mut self) {
+		self.keep = true;
+	}
+
+	/// Returns the path of the directory without removing it.
+	#[inline]
+	pub fn into_path(mut self) -> PathBuf {
+		self.keep = true;
+		std::mem::take(&mut self.path)
+	}
+
+	/// Removes the directory now, returning any errors instead of ignoring them like dropping does.
+	pub fn close(mut self) -> io::Result<()> {
+		self.keep = true;
+		super::remove_dir_all(&self.path)
+	}
+}
+
+/// Creates a directory only the current user can access on unix, since the temporary directory is usually shared.
+fn create_private_dir(path: &Path) -> io::Result<()> {
+	let mut builder = std::fs::DirBuilder::new();
+	#[cfg(unix)]
+	std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+	builder.create(path)
+}
+
+impl AsRef<Path> for TempDir {
+	#[inline]
+	fn as_ref(&self) -> &Path {
+		&self.path
+	}
+}
+
+impl Drop for TempDir {
+	fn drop(&mut self) {
+		if !self.keep {
+			let _ = std::fs::remove_dir_all(&self.path);
+		}
+	}
+}
+
+/// A uniquely named file in [env::temp_dir](std::env::temp_dir), open for reading and writing, that's removed when dropped.
+/// On unix only the current user can access it.
+///
+/// # Examples
+/// ```
+/// use nil::prelude::*;
+/// use nil::fs::{self, TempFile};
+/// use std::io::{Read, Seek, Write};
+///
+/// let mut file = TempFile::with_suffix(".ron").unwrap();
+/// assert!(file.path().s().ends_with(".ron"));
+///
+/// write!(file, "(width: 1280, height: 720)").unwrap();
+/// file.rewind().unwrap();
+/// let mut contents = String::new();
+/// file.read_to_string(&mut contents).unwrap();
+/// assert_eq!(contents, "(width: 1280, height: 720)");
+///
+/// let path = file.path().to_path_buf();
+/// drop(file);
+/// assert!(!fs::exists(&path).unwrap());
+/// ```
+#[derive(Debug)]
+pub struct TempFile {
+	path: PathBuf,
+	/// Only [None] once dropped or consumed, so it can be closed before being removed.
+	file: Option<File>,
+	keep: bool,
+}
+
+impl TempFile {
+	/// Creates a new file in [env::temp_dir](std::env::temp_dir).
+	pub fn new() -> io::Result<Self> {
+		Self::new_in(std::env::temp_dir(), DEFAULT_PREFIX, "")
+	}
+
+	/// Creates a new file in [env::temp_dir](std::env::temp_dir), with a name ending in `suffix`, like an extension.
+	pub fn with_suffix(suffix: &str) -> io::Result<Self> {
+		Self::new_in(std::env::temp_dir(), DEFAULT_PREFIX, suffix)
+	}
+
+	/// Creates a new file in `dir`, with a name starting with `prefix` and ending with `suffix`.
+	pub fn new_in(dir: impl AsRef<Path>, prefix: impl AsRef<OsStr>, suffix: &str) -> io::Result<Self> {
+		let dir = dir.as_ref();
+		let (file, path) = super::create_unique_file(dir, prefix.as_ref(), suffix, true)
+			.map_err(io_add_msg!("failed to create a temporary file in {dir:?}"))?;
+		Ok(Self { path, file: Some(file), keep: false })
+	}
+
+	#[inline]
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	#[inline]
+	pub fn as_file(&self) -> &File {
+		// Only taken out when dropped or consumed.
+		self.file.as_ref().unwrap()
+	}
+
+	#[inline]
+	pub fn as_file_mut(&mut self) -> &mut File {
+		self.file.as_mut().unwrap()
+	}
+
+	/// Stops the file from being removed when dropped.
+	#[inline]
+	pub fn keep(&mut self) {
+		self.keep = true;
+	}
+
+	/// Closes the file and returns its path without removing it.
+	#[inline]
+	pub fn into_path(mut self) -> PathBuf {
+		self.keep = true;
+		self.file = None;
+		std::mem::take(&mut self.path)
+	}
+
+	/// Closes and removes the file now, returning any errors instead of ignoring them like dropping does.
+	pub fn close(mut self) -> io::Result<()> {
+		self.keep = true;
+		self.file = None;
+		super::remove_file(&self.path)
+	}
+
+	#[inline]
+	fn parts(&mut self) -> (&mut File, &Path) {
+		(self.file.as_mut().unwrap(), &self.path)
+	}
+}
+
+impl AsRef<Path> for TempFile {
+	#[inline]
+	fn as_ref(&self) -> &Path {
+		&self.path
+	}
+}
+
+impl Read for TempFile {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let (file, path) = self.parts();
+		file.read(buf).map_err(io_add_msg!("failed to read {path:?}"))
+	}
+}
+
+impl Write for TempFile {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		let (file, path) = self.parts();
+		file.write(buf).map_err(io_add_msg!("failed to write {path:?}"))
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		let (file, path) = self.parts();
+		file.flush().map_err(io_add_msg!("failed to flush {path:?}"))
+	}
+}
+
+impl Seek for TempFile {
+	fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+		let (file, path) = self.parts();
+		file.seek(pos).map_err(io_add_msg!("failed to seek in {path:?}"))
+	}
+}
+
+impl Drop for TempFile {
+	fn drop(&mut self) {
+		// Closed first, as open files can't be removed on windows.
+		self.file = None;
+		if !self.keep {
+			let _ = std::fs::remove_file(&self.path);
+		}
+	}
+}
@@ -0,0 +1,304 @@
+use std::cmp::Ordering;
+use std::ffi::OsStr;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::io_add_msg;
+
+use super::{FileType, Metadata};
+
+/// Recursively walks `root` depth first, see [Walk] for options.
+///
+/// `root` itself is the first entry, at depth 0. Symbolic links to directories aren't followed unless [follow_links](Walk::follow_links) is set,
+/// but `root` is always followed. Errors have the path they happened at attached, and don't stop the walk.
+///
+/// # Examples
+/// ```
+/// use nil::fs::{self, TempDir};
+///
+/// let dir = TempDir::new().unwrap();
+/// let root = dir.path();
+/// fs::create_dir_all(root.join("assets/textures")).unwrap();
+/// fs::create_dir_all(root.join(".git/objects")).unwrap();
+/// fs::write(root.join("assets/textures/grass.png"), "").unwrap();
+/// fs::write(root.join("assets/level.map"), "").unwrap();
+/// fs::write(root.join("Cargo.toml"), "").unwrap();
+///
+/// let relative = |entry: fs::WalkEntry| entry.path().strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/");
+///
+/// let files: Vec<String> = fs::walk(root).files_only().sort_by_file_name().map(|entry| relative(entry.unwrap())).collect();
+/// assert_eq!(files, ["Cargo.toml", "assets/level.map", "assets/textures/grass.png"]);
+///
+/// let dirs: Vec<String> = fs::walk(root)
+///     .dirs_only()
+///     .min_depth(1)
+///     .prune(|entry| entry.file_name() == ".git")
+///     .sort_by_file_name()
+///     .map(|entry| relative(entry.unwrap()))
+///     .collect();
+/// assert_eq!(dirs, ["assets", "assets/textures"]);
+///
+/// assert_eq!(fs::walk(root).max_depth(1).count(), 4);
+///
+/// let err = fs::walk(root.join("missing")).next().unwrap().unwrap_err();
+/// assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+/// assert!(err.to_string().contains("missing"));
+/// ```
+pub fn walk(root: impl AsRef<Path>) -> Walk {
+	Walk {
+		root: Some(root.as_ref().to_path_buf()),
+		min_depth: 0,
+		max_depth: usize::MAX,
+		follow_links: false,
+		yield_files: true,
+		yield_dirs: true,
+		sort: None,
+		prune: None,
+		stack: Vec::new(),
+	}
+}
+
+type SortFn = Box<dyn FnMut(&WalkEntry, &WalkEntry) -> Ordering>;
+type PruneFn = Box<dyn FnMut(&WalkEntry) -> bool>;
+
+/// Iterator returned by [walk], with builder methods to configure it.
+pub struct Walk {
+	/// Taken out when the walk starts.
+	root: Option<PathBuf>,
+	min_depth: usize,
+	max_depth: usize,
+	follow_links: bool,
+	yield_files: bool,
+	yield_dirs: bool,
+	sort: Option<SortFn>,
+	prune: Option<PruneFn>,
+	/// Directories currently being walked, the last being the deepest.
+	stack: Vec<WalkDir>,
+}
+
+struct WalkDir {
+	entries: std::vec::IntoIter<io::Result<WalkEntry>>,
+	/// Canonical path of the directory, only known when following links.
+	canonical: Option<PathBuf>,
+}
+
+impl Walk {
+	/// Skips entries shallower than `depth`, they're still walked through.
+	pub fn min_depth(mut self, depth: usize) -> Self {
+		self.min_depth = depth;
+		self
+	}
+
+	/// Doesn't go deeper than `depth`, where `root` is at 0 and its entries at 1.
+	pub fn max_depth(mut self, depth: usize) -> Self {
+		self.max_depth = depth;
+		self
+	}
+
+	/// Walks into symbolic links to directories. Links back to a directory being walked return an error instead of looping forever.
+	pub fn follow_links(mut self, follow: bool) -> Self {
+		self.follow_links = follow;
+		self
+	}
+
+	/// Only returns entries that aren't directories.
+	pub fn files_only(mut self) -> Self {
+		self.yield_files = true;
+		self.yield_dirs = false;
+		self
+	}
+
+	/// Only returns directories.
+	pub fn dirs_only(mut self) -> Self {
+		self.yield_files = false;
+		self.yield_dirs = true;
+		self
+	}
+
+	/// Walks the entries of each directory in the order given by `compare`.
+	pub fn sort_by(mut self, compare: impl FnMut(&WalkEntry, &WalkEntry) -> Ordering + 'static) -> Self {
+		self.sort = Some(Box::new(compare));
+		self
+	}
+
+	/// Walks the entries of each directory in order of their file names.
+	pub fn sort_by_file_name(self) -> Self {
+		self.sort_by(|a, b| a.file_name().cmp(b.file_name()))
+	}
+
+	/// Skips entries `predicate` returns `true` for, including everything in them if they're directories.
+	pub fn prune(mut self, predicate: impl FnMut(&WalkEntry) -> bool + 'static) -> Self {
+		self.prune = Some(Box::new(predicate));
+		self
+	}
+
+	/// Decides whether to return `entry`, and starts walking it if it's a directory.
+	fn visit(&mut self, entry: WalkEntry) -> Option<io::Result<WalkEntry>> {
+		if self.prune.as_mut().is_some_and(|prune| prune(&entry)) {
+			return None;
+		}
+
+		if entry.is_dir() && entry.depth < self.max_depth {
+			let dir = self.read_dir(&entry);
+			self.stack.push(dir);
+		}
+
+		let wanted = if entry.is_dir() { self.yield_dirs } else { self.yield_files };
+		(wanted && entry.depth >= self.min_depth).then_some(Ok(entry))
+	}
+
+	/// Reads the entries of `dir`. Errors are stored as its entries, so they're returned after `dir` itself.
+	fn read_dir(&mut self, dir: &WalkEntry) -> WalkDir {
+		let path = dir.path();
+		let error = |err: io::Error| WalkDir { entries: vec![Err(err)].into_iter(), canonical: None };
+
+		let canonical = if self.follow_links {
+			let canonical = match std::fs::canonicalize(path).map_err(io_add_msg!("failed to canonicalize {path:?}")) {
+				Ok(canonical) => canonical,
+				Err(err) => return error(err),
+			};
+			if let Some(ancestor) = self.stack.iter().filter_map(|dir| dir.canonical.as_ref()).find(|ancestor| **ancestor == canonical) {
+				return error(io::Error::other(format!("{path:?} links to {ancestor:?}, which is already being walked")));
+			}
+			Some(canonical)
+		} else {
+			None
+		};
+
+		let read_dir = match std::fs::read_dir(path).map_err(io_add_msg!("failed to read directory {path:?}")) {
+			Ok(read_dir) => read_dir,
+			Err(err) => return error(err),
+		};
+
+		let mut entries = Vec::new();
+		let mut errors = Vec::new();
+		for entry in read_dir {
+			match entry.map_err(io_add_msg!("failed to read directory {path:?}")) {
+				Ok(entry) => match WalkEntry::from_dir_entry(entry, dir.depth + 1, self.follow_links) {
+					Ok(entry) => entries.push(entry),
+					Err(err) => errors.push(err),
+				},
+				Err(err) => errors.push(err),
+			}
+		}
+
+		if let Some(sort) = &mut self.sort {
+			entries.sort_by(|a, b| sort(a, b));
+		}
+
+		let entries: Vec<_> = errors.into_iter().map(Err).chain(entries.into_iter().map(Ok)).collect();
+		WalkDir { entries: entries.into_iter(), canonical }
+	}
+}
+
+impl Iterator for Walk {
+	type Item = io::Result<WalkEntry>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if let Some(root) = self.root.take() {
+			let file_type = match std::fs::metadata(&root).map_err(io_add_msg!("failed to read metadata of {root:?}")) {
+				Ok(metadata) => metadata.file_type(),
+				Err(err) => return Some(Err(err)),
+			};
+			let entry = WalkEntry { path: root, depth: 0, file_type, followed: true };
+			if let Some(item) = self.visit(entry) {
+				return Some(item);
+			}
+		}
+
+		loop {
+			let entry = match self.stack.last_mut()?.entries.next() {
+				Some(Ok(entry)) => entry,
+				Some(Err(err)) => return Some(Err(err)),
+				None => {
+					self.stack.pop();
+					continue;
+				}
+			};
+			if let Some(item) = self.visit(entry) {
+				return Some(item);
+			}
+		}
+	}
+}
+
+/// A file, directory or symbolic link found by [walk].
+#[derive(Debug, Clone)]
+pub struct WalkEntry {
+	path: PathBuf,
+	depth: usize,
+	file_type: FileType,
+	/// Whether `file_type` is of what a symbolic link points to, rather than the link itself.
+	followed: bool,
+}
+
+impl WalkEntry {
+	fn from_dir_entry(entry: std::fs::DirEntry, depth: usize, follow_links: bool) -> io::Result<Self> {
+		let path = entry.path();
+		let mut file_type = entry.file_type().map_err(io_add_msg!("failed to read metadata of {path:?}"))?;
+		let mut followed = false;
+
+		if follow_links && file_type.is_symlink() {
+			// Broken links are returned as links.
+			if let Ok(metadata) = std::fs::metadata(&path) {
+				file_type = metadata.file_type();
+				followed = true;
+			}
+		}
+
+		Ok(Self { path, depth, file_type, followed })
+	}
+
+	#[inline]
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	#[inline]
+	pub fn into_path(self) -> PathBuf {
+		self.path
+	}
+
+	/// The last component of the path, or the whole path if it has none, like `..`.
+	#[inline]
+	pub fn file_name(&self) -> &OsStr {
+		self.path.file_name().unwrap_or(self.path.as_os_str())
+	}
+
+	/// How many directories deep this is from the root, where the root is at 0.
+	#[inline]
+	pub fn depth(&self) -> usize {
+		self.depth
+	}
+
+	/// Type of the entry, or what it links to if it's a symbolic link that was followed.
+	#[inline]
+	pub fn file_type(&self) -> FileType {
+		self.file_type
+	}
+
+	#[inline]
+	pub fn is_dir(&self) -> bool {
+		self.file_type.is_dir()
+	}
+
+	#[inline]
+	pub fn is_file(&self) -> bool {
+		self.file_type.is_file()
+	}
+
+	/// Whether this is a symbolic link that wasn't followed.
+	#[inline]
+	pub fn is_symlink(&self) -> bool {
+		self.file_type.is_symlink()
+	}
+
+	/// Path-aware metadata of the entry, following it if it's a followed symbolic link.
+	pub fn metadata(&self) -> io::Result<Metadata> {
+		if self.followed {
+			super::metadata(&self.path)
+		} else {
+			super::symlink_metadata(&self.path)
+		}
+	}
+}
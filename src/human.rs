@@ -0,0 +1,458 @@
+//! Human readable [Duration]s and byte sizes, that can be parsed back from the same format.
+//!
+//! Both print exactly by default so parsing the output gives back the same value. A precision (`{:.1}`) rounds to that many decimals
+//! and pads with zeros, like it does for floats, except for whole bytes and nanoseconds which are never fractional.
+//! Width, fill and alignment work like they do for strings.
+//!
+//! # Examples
+//! ```
+//! use nil::human::{HumanBytes, HumanDuration};
+//! use std::time::Duration;
+//!
+//! assert_eq!(HumanDuration(Duration::from_millis(3_723_400)).to_string(), "1h 2m 3.4s");
+//! assert_eq!(format!("{:.1}", HumanDuration(Duration::from_micros(1_234_567))), "1.2s");
+//! assert_eq!("250ms".parse::<HumanDuration>().unwrap().0, Duration::from_millis(250));
+//!
+//! assert_eq!(HumanBytes::binary(1536).to_string(), "1.5KiB");
+//! assert_eq!(HumanBytes::si(1_500_000).to_string(), "1.5MB");
+//! assert_eq!(format!("{:.2}", HumanBytes::binary(1_234_567)), "1.18MiB");
+//! assert_eq!("1.5GiB".parse::<HumanBytes>().unwrap().bytes, 1_610_612_736);
+//!
+//! assert_eq!(format!("[{:>8}|{:<6}|{:^7.1}]", HumanBytes::binary(1024), HumanBytes::si(12), HumanDuration(Duration::from_millis(2_500))), "[    1KiB|12B   | 2.5s  ]");
+//! ```
+
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use nil_derive::NilError;
+
+/// Why parsing a [HumanDuration] or [HumanBytes] failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, NilError)]
+pub enum ParseHumanError {
+	#[error("nothing to parse")]
+	Empty,
+	#[error("invalid number")]
+	InvalidNumber,
+	#[error("unknown unit")]
+	UnknownUnit,
+	#[error("value is too large")]
+	Overflow,
+}
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Displays a [Duration] like `1d 2h 3m 4.5s`, or `250ms` if it's shorter than a second. Parses the same format back.
+///
+/// Parsing accepts any amount of `d`, `h`, `m`, `s`, `ms`, `us`/`µs` and `ns` components in any order, optionally separated by whitespace.
+///
+/// # Examples
+/// ```
+/// use nil::human::HumanDuration;
+/// use std::time::Duration;
+///
+/// assert_eq!(HumanDuration(Duration::ZERO).to_string(), "0s");
+/// assert_eq!(HumanDuration(Duration::from_secs(90_061)).to_string(), "1d 1h 1m 1s");
+/// assert_eq!(HumanDuration(Duration::from_nanos(60_000_000_001)).to_string(), "1m 0.000000001s");
+/// assert_eq!(HumanDuration(Duration::from_nanos(1_500)).to_string(), "1.5µs");
+/// assert_eq!(format!("{:.2}", HumanDuration(Duration::from_nanos(999_999_999))), "1.00s");
+/// assert_eq!(format!("{:.1}", HumanDuration(Duration::from_millis(62_000))), "1m 2.0s");
+/// assert_eq!(format!("{:.3}", HumanDuration(Duration::from_micros(1_500))), "1.500ms");
+/// assert_eq!(format!("{:.1}", HumanDuration(Duration::from_nanos(7))), "7ns");
+/// // Seconds are always written with a precision, even when there are none.
+/// assert_eq!(format!("{:.1}", HumanDuration(Duration::from_nanos(59_960_000_000))), "1m 0.0s");
+/// assert_eq!(format!("{:.1}", HumanDuration(Duration::from_nanos(3_599_999_999_999))), "1h 0.0s");
+/// assert_eq!(format!("{:.1}", HumanDuration(Duration::ZERO)), "0.0s");
+///
+/// let parse = |s: &str| s.parse::<HumanDuration>().map(|duration| duration.0);
+/// assert_eq!(parse("1.5h"), Ok(Duration::from_secs(5400)));
+/// assert_eq!(parse("2m30s"), Ok(Duration::from_secs(150)));
+/// assert_eq!(parse(" 1s 500ms "), Ok(Duration::from_millis(1500)));
+/// assert!(parse("").is_err());
+/// assert!(parse("5 parsecs").is_err());
+///
+/// // Round trips exactly.
+/// for duration in [Duration::from_nanos(1_500), Duration::new(90_061, 5), Duration::MAX] {
+///     assert_eq!(parse(&HumanDuration(duration).to_string()), Ok(duration));
+/// }
+/// # // Every duration up to 100µs, then random ones of every magnitude.
+/// # let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+/// # let mut random = || {
+/// #     seed ^= seed << 13;
+/// #     seed ^= seed >> 7;
+/// #     seed ^= seed << 17;
+/// #     seed
+/// # };
+/// # let random_durations = std::iter::repeat_with(|| Duration::new(random() >> (random() % 64), (random() % 1_000_000_000) as u32));
+/// # for duration in (0..=100_000).map(Duration::from_nanos).chain(random_durations.take(10_000)) {
+/// #     assert_eq!(parse(&HumanDuration(duration).to_string()), Ok(duration));
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HumanDuration(pub Duration);
+
+impl HumanDuration {
+	const UNITS: &'static [(&'static str, u128)] = &[
+		("d", 86_400 * NANOS_PER_SEC),
+		("h", 3_600 * NANOS_PER_SEC),
+		("m", 60 * NANOS_PER_SEC),
+		("s", NANOS_PER_SEC),
+		("ms", 1_000_000),
+		("us", 1_000),
+		("µs", 1_000),
+		("ns", 1),
+	];
+}
+
+impl fmt::Display for HumanDuration {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let precision = f.precision();
+		pad(f, |out| write_duration(out, self.0.as_nanos(), precision))
+	}
+}
+
+fn write_duration(out: &mut dyn fmt::Write, mut nanos: u128, precision: Option<usize>) -> fmt::Result {
+	// The unit of the last component, seconds unless the duration is shorter than that.
+	let (unit, unit_nanos) = match nanos {
+		0 | 1_000_000_000.. => ("s", NANOS_PER_SEC),
+		1_000_000.. => ("ms", 1_000_000),
+		1_000.. => ("µs", 1_000),
+		_ => ("ns", 1),
+	};
+
+	if let Some(precision) = precision {
+		nanos = round_nanos(nanos, unit_nanos, precision);
+		// Rounding up can reach the next unit.
+		if nanos >= unit_nanos * 1_000 && unit_nanos < NANOS_PER_SEC {
+			return write_duration(out, nanos, Some(precision));
+		}
+	}
+
+	if unit_nanos < NANOS_PER_SEC {
+		write_decimal(out, nanos, unit_nanos, precision)?;
+		return out.write_str(unit);
+	}
+
+	let mut secs = nanos / NANOS_PER_SEC;
+	let subsec_nanos = nanos % NANOS_PER_SEC;
+	let mut first = true;
+
+	for (unit, unit_secs) in [("d", 86_400), ("h", 3_600), ("m", 60)] {
+		if secs >= unit_secs {
+			if !first {
+				out.write_str(" ")?;
+			}
+			write!(out, "{}{unit}", secs / unit_secs)?;
+			secs %= unit_secs;
+			first = false;
+		}
+	}
+
+	// Always written with a precision, so it's padded like the rest.
+	if first || precision.is_some() || secs > 0 || subsec_nanos > 0 {
+		if !first {
+			out.write_str(" ")?;
+		}
+		write_decimal(out, secs * NANOS_PER_SEC + subsec_nanos, NANOS_PER_SEC, precision)?;
+		out.write_str("s")?;
+	}
+
+	Ok(())
+}
+
+impl FromStr for HumanDuration {
+	type Err = ParseHumanError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut rest = s.trim_start();
+		if rest.is_empty() {
+			return Err(ParseHumanError::Empty);
+		}
+
+		let mut nanos: u128 = 0;
+		while !rest.is_empty() {
+			let (number, after) = split_number(rest);
+			let (unit, after) = split_unit(after.trim_start());
+			let unit_nanos = Self::UNITS.iter().find(|(name, _)| *name == unit).ok_or(ParseHumanError::UnknownUnit)?.1;
+
+			nanos = nanos.checked_add(parse_scaled(number, unit_nanos)?).ok_or(ParseHumanError::Overflow)?;
+			rest = after.trim_start();
+		}
+
+		duration_from_nanos(nanos).map(Self).ok_or(ParseHumanError::Overflow)
+	}
+}
+
+impl From<Duration> for HumanDuration {
+	#[inline]
+	fn from(value: Duration) -> Self {
+		Self(value)
+	}
+}
+
+impl From<HumanDuration> for Duration {
+	#[inline]
+	fn from(value: HumanDuration) -> Self {
+		value.0
+	}
+}
+
+/// Whether [HumanBytes] uses powers of 1024 (`KiB`, `MiB`, ...) or 1000 (`kB`, `MB`, ...).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ByteUnits {
+	#[default]
+	Binary,
+	Si,
+}
+
+impl ByteUnits {
+	const BINARY: [&'static str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+	const SI: [&'static str; 7] = ["B", "kB", "MB", "GB", "TB", "PB", "EB"];
+
+	#[inline]
+	fn base(self) -> u128 {
+		match self {
+			Self::Binary => 1024,
+			Self::Si => 1000,
+		}
+	}
+
+	#[inline]
+	fn names(self) -> &'static [&'static str; 7] {
+		match self {
+			Self::Binary => &Self::BINARY,
+			Self::Si => &Self::SI,
+		}
+	}
+}
+
+/// Displays a byte count in the biggest unit it has at least one of, like `1.5KiB` or `1.5kB`. Parses the same format back.
+///
+/// Parsing ignores the case of units and allows whitespace before them, a number without a unit is in bytes.
+/// The parsed [units](Self::units) are [Si](ByteUnits::Si) if an SI unit was used, [Binary](ByteUnits::Binary) otherwise.
+///
+/// # Examples
+/// ```
+/// use nil::human::{ByteUnits, HumanBytes};
+///
+/// assert_eq!(HumanBytes::binary(0).to_string(), "0B");
+/// assert_eq!(HumanBytes::binary(1023).to_string(), "1023B");
+/// assert_eq!(HumanBytes::binary(1024 * 1024).to_string(), "1MiB");
+/// assert_eq!(HumanBytes::si(999_999).to_string(), "999.999kB");
+/// assert_eq!(format!("{:.1}", HumanBytes::si(999_999)), "1000.0kB");
+/// assert_eq!(format!("{:.2}", HumanBytes::binary(1024)), "1.00KiB");
+/// assert_eq!(format!("{:.2}", HumanBytes::binary(1000)), "1000B");
+/// assert_eq!(HumanBytes::si(u64::MAX).to_string(), "18.446744073709551615EB");
+///
+/// assert_eq!("12 kb".parse(), Ok(HumanBytes::si(12_000)));
+/// assert_eq!("2MiB".parse(), Ok(HumanBytes::binary(2 * 1024 * 1024)));
+/// assert_eq!("512".parse(), Ok(HumanBytes::binary(512)));
+/// assert!("1.5 bits".parse::<HumanBytes>().is_err());
+/// assert!("16EiB".parse::<HumanBytes>().is_err());
+///
+/// // Round trips exactly.
+/// for human in [HumanBytes::binary(1023), HumanBytes::binary(1_234_567), HumanBytes::si(999_999), HumanBytes::si(u64::MAX)] {
+///     assert_eq!(human.to_string().parse::<HumanBytes>().map(|parsed| parsed.bytes), Ok(human.bytes));
+/// }
+/// # // Every size up to 100kB, then random ones of every magnitude.
+/// # let mut seed = 0x9e37_79b9_7f4a_7c15_u64;
+/// # let mut random = || {
+/// #     seed ^= seed << 13;
+/// #     seed ^= seed >> 7;
+/// #     seed ^= seed << 17;
+/// #     seed
+/// # };
+/// # let random_sizes: Vec<u64> = std::iter::repeat_with(|| random() >> (random() % 64)).take(10_000).collect();
+/// # for units in [ByteUnits::Binary, ByteUnits::Si] {
+/// #     for &bytes in (0..=100_000).collect::<Vec<_>>().iter().chain(&random_sizes).chain(&[u64::MAX]) {
+/// #         let human = HumanBytes { bytes, units };
+/// #         assert_eq!(human.to_string().parse::<HumanBytes>().map(|human| human.bytes), Ok(bytes));
+/// #     }
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct HumanBytes {
+	pub bytes: u64,
+	pub units: ByteUnits,
+}
+
+impl HumanBytes {
+	#[inline]
+	pub const fn binary(bytes: u64) -> Self {
+		Self { bytes, units: ByteUnits::Binary }
+	}
+
+	#[inline]
+	pub const fn si(bytes: u64) -> Self {
+		Self { bytes, units: ByteUnits::Si }
+	}
+}
+
+impl fmt::Display for HumanBytes {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let precision = f.precision();
+		pad(f, |out| self.write(out, precision))
+	}
+}
+
+impl HumanBytes {
+	fn write(&self, out: &mut dyn fmt::Write, precision: Option<usize>) -> fmt::Result {
+		let bytes = self.bytes as u128;
+		let base = self.units.base();
+
+		let mut unit = 1;
+		let mut name = "B";
+		for next in &self.units.names()[1..] {
+			if bytes < unit * base {
+				break;
+			}
+			unit *= base;
+			name = next;
+		}
+
+		match precision {
+			Some(precision) if unit > 1 => {
+				let pow = 10_u128.pow(precision.min(18) as u32);
+				// Rounded to the nearest, fits since `bytes` is at most a `u64`.
+				let scaled = (bytes * pow * 2 / unit).div_ceil(2);
+				write_decimal(out, scaled, pow, Some(precision))?;
+			}
+			_ => write_decimal(out, bytes, unit, None)?,
+		}
+
+		out.write_str(name)
+	}
+}
+
+impl FromStr for HumanBytes {
+	type Err = ParseHumanError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let s = s.trim();
+		if s.is_empty() {
+			return Err(ParseHumanError::Empty);
+		}
+
+		let (number, unit) = split_number(s);
+		let unit = unit.trim_start();
+
+		let (units, power) = if unit.is_empty() {
+			(ByteUnits::Binary, 0)
+		} else {
+			[ByteUnits::Binary, ByteUnits::Si]
+				.into_iter()
+				.find_map(|units| {
+					let power = units.names().iter().position(|name| name.eq_ignore_ascii_case(unit))?;
+					Some((units, power as u32))
+				})
+				.ok_or(ParseHumanError::UnknownUnit)?
+		};
+
+		let bytes = parse_scaled(number, units.base().pow(power))?;
+		let bytes = u64::try_from(bytes).map_err(|_| ParseHumanError::Overflow)?;
+
+		Ok(Self { bytes, units })
+	}
+}
+
+/// Writes `value / unit` exactly, with trailing zeros trimmed. `unit` must divide a power of 10 or be a power of 2, so the decimal ends.
+/// With a precision, exactly that many decimals are written instead, so `value` should already be rounded to it.
+fn write_decimal(out: &mut dyn fmt::Write, value: u128, unit: u128, precision: Option<usize>) -> fmt::Result {
+	write!(out, "{}", value / unit)?;
+
+	let mut remainder = value % unit;
+	let precision = precision.filter(|_| unit > 1);
+	if precision.map_or(remainder == 0, |precision| precision == 0) {
+		return Ok(());
+	}
+
+	out.write_str(".")?;
+	let mut digits = 0;
+	while precision.map_or(remainder != 0, |precision| digits < precision) {
+		remainder *= 10;
+		write!(out, "{}", remainder / unit)?;
+		remainder %= unit;
+		digits += 1;
+	}
+
+	Ok(())
+}
+
+/// Writes what `write` writes, padded to the width of `f` with its fill and alignment, left aligned by default like strings.
+///
+/// The output is written twice, once to measure it, since there's nowhere to buffer it without an allocator.
+fn pad(f: &mut fmt::Formatter<'_>, write: impl Fn(&mut dyn fmt::Write) -> fmt::Result) -> fmt::Result {
+	let Some(width) = f.width() else { return write(f) };
+
+	struct CharCount(usize);
+	impl fmt::Write for CharCount {
+		fn write_str(&mut self, s: &str) -> fmt::Result {
+			self.0 += s.chars().count();
+			Ok(())
+		}
+	}
+
+	let mut count = CharCount(0);
+	write(&mut count)?;
+	let padding = width.saturating_sub(count.0);
+	let (before, after) = match f.align() {
+		Some(fmt::Alignment::Right) => (padding, 0),
+		Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+		Some(fmt::Alignment::Left) | None => (0, padding),
+	};
+
+	let fill = f.fill();
+	for _ in 0..before {
+		fmt::Write::write_char(f, fill)?;
+	}
+	write(f)?;
+	for _ in 0..after {
+		fmt::Write::write_char(f, fill)?;
+	}
+	Ok(())
+}
+
+/// Rounds `nanos` to the nearest multiple of `unit / 10^precision`, `unit` being a power of 10.
+fn round_nanos(nanos: u128, unit: u128, precision: usize) -> u128 {
+	let pow = 10_u128.pow(precision.min(9) as u32);
+	if pow >= unit {
+		return nanos;
+	}
+	let step = unit / pow;
+	(nanos + step / 2) / step * step
+}
+
+/// Splits a leading decimal number off of `s`.
+fn split_number(s: &str) -> (&str, &str) {
+	let end = s.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(s.len());
+	s.split_at(end)
+}
+
+/// Splits a leading unit off of `s`.
+fn split_unit(s: &str) -> (&str, &str) {
+	let end = s.find(|c: char| !c.is_alphabetic()).unwrap_or(s.len());
+	s.split_at(end)
+}
+
+/// Parses a decimal `number` multiplied by `unit`, rounded to the nearest integer.
+fn parse_scaled(number: &str, unit: u128) -> Result<u128, ParseHumanError> {
+	let (int, frac) = number.split_once('.').unwrap_or((number, ""));
+	if (int.is_empty() && frac.is_empty()) || !frac.bytes().all(|b| b.is_ascii_digit()) {
+		return Err(ParseHumanError::InvalidNumber);
+	}
+
+	let int: u128 = if int.is_empty() { 0 } else { int.parse().map_err(|_| ParseHumanError::Overflow)? };
+
+	// Evaluates the fraction times `2 * unit` from the last digit backwards, flooring at each step still gives the exact floor.
+	let mut frac_scaled = 0;
+	for digit in frac.bytes().rev() {
+		frac_scaled = ((digit - b'0') as u128 * unit * 2 + frac_scaled) / 10;
+	}
+
+	int.checked_mul(unit).and_then(|int| int.checked_add(frac_scaled.div_ceil(2))).ok_or(ParseHumanError::Overflow)
+}
+
+fn duration_from_nanos(nanos: u128) -> Option<Duration> {
+	let secs = u64::try_from(nanos / NANOS_PER_SEC).ok()?;
+	Some(Duration::new(secs, (nanos % NANOS_PER_SEC) as u32))
+}
@@ -1,53 +1,153 @@
 #![doc = include_str!("../README.md")]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::{borrow::ToOwned, boxed::Box, string::{String, ToString}};
 
 pub use smart_default;
+pub use nil_derive;
 pub use once_cell;
+#[cfg(feature = "std")]
 pub use parking_lot;
 
-/// Extra std imports that i use a lot.
-pub mod std_prelude {
-	pub use std::path::{Path, PathBuf};
-	pub use std::fs;
-	pub use std::io;
-	pub use std::thread;
-	pub use std::collections::{HashMap, BTreeMap, HashSet, BTreeSet};
-	pub use std::sync::Arc;
-	pub use std::sync::atomic::*;
-	pub use std::error::Error;
-	pub use std::borrow::Cow;
-	pub use std::time::{Duration, Instant};
-	pub use std::mem;
-	pub use std::fmt;
-	pub use std::env;
-	pub use std::ptr;
-	pub use std::process;
-	pub use std::any::*;
-	pub use std::f32::consts::{
-		PI,
+pub mod angle;
+pub mod human;
+pub mod math;
+#[cfg(feature = "std")]
+pub mod error;
+#[cfg(feature = "std")]
+pub mod fs;
+#[cfg(feature = "std")]
+pub mod path;
+#[cfg(feature = "std")]
+pub mod report;
+#[cfg(feature = "std")]
+pub mod sync;
+#[cfg(feature = "std")]
+pub mod time;
+
+/// Math constants for [f32] and [f64] (suffixed with `_F64`), and generic helpers from [math].
+pub mod math_prelude {
+	pub use core::f32::consts::{
+		E,
+		FRAC_1_PI,
+		FRAC_1_SQRT_2,
+		FRAC_2_PI,
+		FRAC_2_SQRT_PI,
 		FRAC_PI_2,
 		FRAC_PI_3,
 		FRAC_PI_4,
 		FRAC_PI_6,
 		FRAC_PI_8,
+		LN_2,
+		LN_10,
+		LOG2_10,
+		LOG2_E,
+		LOG10_2,
+		LOG10_E,
+		PI,
+		SQRT_2,
+		TAU,
 	};
-	pub use std::f64::consts::{
-		PI as PI_F64,
+	pub use core::f64::consts::{
+		E as E_F64,
+		FRAC_1_PI as FRAC_1_PI_F64,
+		FRAC_1_SQRT_2 as FRAC_1_SQRT_2_F64,
+		FRAC_2_PI as FRAC_2_PI_F64,
+		FRAC_2_SQRT_PI as FRAC_2_SQRT_PI_F64,
 		FRAC_PI_2 as FRAC_PI_2_F64,
 		FRAC_PI_3 as FRAC_PI_3_F64,
 		FRAC_PI_4 as FRAC_PI_4_F64,
 		FRAC_PI_6 as FRAC_PI_6_F64,
 		FRAC_PI_8 as FRAC_PI_8_F64,
+		LN_2 as LN_2_F64,
+		LN_10 as LN_10_F64,
+		LOG2_10 as LOG2_10_F64,
+		LOG2_E as LOG2_E_F64,
+		LOG10_2 as LOG10_2_F64,
+		LOG10_E as LOG10_E_F64,
+		PI as PI_F64,
+		SQRT_2 as SQRT_2_F64,
+		TAU as TAU_F64,
 	};
+	pub use crate::math::{lerp, inverse_lerp, remap, approx_eq, approx_eq_eps, wrap_angle};
+}
+
+/// Extra core imports that i use a lot, available without `std` or `alloc`. Includes [math_prelude].
+pub mod core_prelude {
+	pub use core::sync::atomic::*;
+	pub use core::error::Error;
+	pub use core::time::Duration;
+	pub use core::mem;
+	pub use core::fmt;
+	pub use core::ptr;
+	pub use core::any::*;
+	pub use crate::math_prelude::*;
+}
+
+/// Extra alloc imports that i use a lot, available without `std`. Includes [core_prelude].
+#[cfg(feature = "alloc")]
+pub mod alloc_prelude {
+	pub use crate::core_prelude::*;
+	pub use alloc::{format, vec};
+	pub use alloc::boxed::Box;
+	pub use alloc::string::{String, ToString};
+	pub use alloc::vec::Vec;
+	pub use alloc::collections::{BTreeMap, BTreeSet};
+	#[cfg(target_has_atomic = "ptr")]
+	pub use alloc::sync::Arc;
+	pub use alloc::borrow::{Cow, ToOwned};
+}
+
+/// Extra std imports that i use a lot. Includes [alloc_prelude] and [core_prelude].
+#[cfg(feature = "std")]
+pub mod std_prelude {
+	pub use crate::alloc_prelude::*;
+	pub use std::path::{Path, PathBuf};
+	pub use crate::fs;
+	pub use std::io;
+	pub use std::thread;
+	pub use std::collections::{HashMap, HashSet};
+	pub use std::time::Instant;
+	pub use std::env;
+	pub use std::process;
 }
 
 pub mod prelude {
 	pub use crate::flat;
+	pub use crate::angle::{Radians, Degrees};
+	#[cfg(feature = "std")]
+	pub use crate::global;
+	#[cfg(feature = "std")]
 	pub use crate::io_add_msg;
+	#[cfg(feature = "std")]
+	pub use crate::{time_scope, timed};
+	#[cfg(feature = "std")]
+	pub use crate::error::IoResultExt;
+	#[cfg(feature = "std")]
+	pub use crate::report::Report;
+	#[cfg(feature = "std")]
+	pub use crate::time::Stopwatch;
+	#[cfg(feature = "alloc")]
 	pub use crate::ShortToString;
+	#[cfg(feature = "std")]
+	pub use crate::TryShortToString;
+	#[cfg(feature = "std")]
+	pub use crate::ShortToPath;
+	#[cfg(feature = "std")]
+	pub use crate::path::PathExt;
 
+	#[cfg(feature = "std")]
 	pub use once_cell::sync::Lazy;
+	#[cfg(feature = "std")]
 	pub use parking_lot::{Mutex, MutexGuard, MappedMutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard, MappedRwLockReadGuard};
+	#[cfg(feature = "std")]
+	pub use crate::sync::{Shared, SharedRw, TracedMutex, TracedRwLock, OrderedMutex, OrderedRwLock};
 	pub use smart_default::*;
+	pub use nil_derive::*;
 }
 
 /// Makes defining a flat module (e.g. foo::Baz instead of foo::bar::Baz) easier.
@@ -68,21 +168,307 @@ pub mod prelude {
 ///     bar;
 /// }
 /// ```
+///
+/// By default the module is private and everything in it is re-exported publicly. This can be changed per module:
+/// ```ignore
+/// flat! {
+///     // `mod foo; pub use self::foo::*;`
+///     foo;
+///     // `mod internal; pub(crate) use self::internal::*;`
+///     pub(crate) internal;
+///     // `pub mod render; pub use self::render::*;`, so both `render::Mesh` and `Mesh` work.
+///     pub mod render;
+///     // `mod util; pub use self::util::{Timer, lerp};`
+///     util::{Timer, lerp};
+///     // `mod helpers; use self::helpers::*;`, only usable in this module.
+///     mod helpers;
+///     // Visibilities and selective re-exports can be combined.
+///     pub(crate) mod cache::{Cache};
+/// }
+/// ```
+///
+/// Modules can also be given a `#[path]`, an inline body, or be a directory of modules that are flattened recursively:
+/// ```ignore
+/// flat! {
+///     #[path = "platform/linux.rs"]
+///     platform;
+///     // `mod consts { ... } pub use self::consts::*;`
+///     consts {
+///         pub const MAX_PLAYERS: usize = 4;
+///     }
+///     // Flattens `render/mesh.rs` and `render/texture.rs` into `render`, which is then flattened into this module.
+///     // Note that there is no `;` after the braces, unlike selective re-exports.
+///     pub mod render::{
+///         mesh;
+///         pub(crate) texture;
+///     }
+/// }
+/// ```
+/// Attributes are applied to both the `mod` and `use`, except for `#[path]` which only applies to the `mod`.
+///
+/// Invocations of only `name;` entries, optionally with attributes, are expanded in one pass. Other forms are expanded one entry at a time,
+/// so every entry up to the last one that isn't `name;` adds a few levels of macro recursion, and very big invocations might need a higher `#![recursion_limit]`.
+///
+/// # Examples
+/// ```
+/// use nil::*;
+///
+/// flat! {
+///     consts {
+///         pub const MAX_PLAYERS: usize = 4;
+///     }
+///     pub(crate) mod units {
+///         pub struct Meters(pub f32);
+///     }
+/// }
+///
+/// fn main() {
+///     assert_eq!(MAX_PLAYERS, 4);
+///     let _ = (Meters(1.0), units::Meters(2.0));
+/// }
+/// ```
 #[macro_export]
 macro_rules! flat {
+	// Simple invocations are expanded all at once to avoid hitting the recursion limit in big crates.
+	{$($name:ident ;)*} => {
+		$( mod $name; pub use self::$name::*; )*
+	};
+	// Same for ones with attributes, every entry is expanded separately rather than one after another, so they don't add up.
 	{$($(#[$attr:meta])* $name:ident ;)*} => {
-		$( $(#[$attr])* mod $name; $(#[$attr])* pub use self::$name::*; )*
+		$( $crate::flat! { @attrs [] [] $(#[$attr])* $name ; } )*
+	};
+
+	// Collects attributes one at a time, `#[path]` is kept separate since it can only be applied to the `mod`.
+	{@attrs [$($attr:tt)*] [$($path:tt)*] #[path = $file:literal] $($rest:tt)*} => {
+		$crate::flat! { @attrs [$($attr)*] [#[path = $file]] $($rest)* }
+	};
+	{@attrs [$($attr:tt)*] [$($path:tt)*] #[$meta:meta] $($rest:tt)*} => {
+		$crate::flat! { @attrs [$($attr)* #[$meta]] [$($path)*] $($rest)* }
+	};
+	// Determines the visibility of the `mod` and `use`.
+	{@attrs [$($attr:tt)*] [$($path:tt)*] pub $(($($restriction:tt)+))? mod $name:ident $($rest:tt)*} => {
+		$crate::flat! { @entry [$($attr)*] [$($path)*] [pub $(($($restriction)+))?] [pub $(($($restriction)+))?] $name $($rest)* }
+	};
+	{@attrs [$($attr:tt)*] [$($path:tt)*] pub $(($($restriction:tt)+))? $name:ident $($rest:tt)*} => {
+		$crate::flat! { @entry [$($attr)*] [$($path)*] [] [pub $(($($restriction)+))?] $name $($rest)* }
+	};
+	{@attrs [$($attr:tt)*] [$($path:tt)*] mod $name:ident $($rest:tt)*} => {
+		$crate::flat! { @entry [$($attr)*] [$($path)*] [] [] $name $($rest)* }
+	};
+	{@attrs [$($attr:tt)*] [$($path:tt)*] $name:ident $($rest:tt)*} => {
+		$crate::flat! { @entry [$($attr)*] [$($path)*] [] [pub] $name $($rest)* }
+	};
+
+	// `name;`
+	{@entry [$($attr:tt)*] [$($path:tt)*] [$($mod_vis:tt)*] [$($use_vis:tt)*] $name:ident ; $($rest:tt)*} => {
+		$($attr)* $($path)* $($mod_vis)* mod $name;
+		$($attr)* $($use_vis)* use self::$name::*;
+		$crate::flat! { $($rest)* }
+	};
+	// `name::{A, B};`
+	{@entry [$($attr:tt)*] [$($path:tt)*] [$($mod_vis:tt)*] [$($use_vis:tt)*] $name:ident :: { $($item:tt)* } ; $($rest:tt)*} => {
+		$($attr)* $($path)* $($mod_vis)* mod $name;
+		$($attr)* $($use_vis)* use self::$name::{$($item)*};
+		$crate::flat! { $($rest)* }
+	};
+	// `name::{ a; b; }`
+	{@entry [$($attr:tt)*] [$($path:tt)*] [$($mod_vis:tt)*] [$($use_vis:tt)*] $name:ident :: { $($inner:tt)* } $($rest:tt)*} => {
+		$($attr)* $($path)* $($mod_vis)* mod $name { $crate::flat! { $($inner)* } }
+		$($attr)* $($use_vis)* use self::$name::*;
+		$crate::flat! { $($rest)* }
+	};
+	// `name { ... }`
+	{@entry [$($attr:tt)*] [$($path:tt)*] [$($mod_vis:tt)*] [$($use_vis:tt)*] $name:ident { $($body:tt)* } $($rest:tt)*} => {
+		$($attr)* $($path)* $($mod_vis)* mod $name { $($body)* }
+		$($attr)* $($use_vis)* use self::$name::*;
+		$crate::flat! { $($rest)* }
+	};
+
+	{$($rest:tt)+} => {
+		$crate::flat! { @attrs [] [] $($rest)+ }
 	};
 }
 
-/// Expands to a function that prepends a message to an io error, to be used with `Result::map_err`.
+/// Expands to a function that adds a message to an io error, to be used with `Result::map_err`.
+///
+/// The resulting error keeps the [ErrorKind](std::io::ErrorKind) of the original, and wraps it in a [ContextError](crate::error::ContextError),
+/// so the original error is still reachable through [Error::source](std::error::Error::source).
+///
+/// # Examples
+/// ```
+/// use nil::prelude::*;
+/// use nil::error::Chain;
+/// use std::io;
+///
+/// let path = "foo.txt";
+/// let result: io::Result<()> = Err(io::Error::new(io::ErrorKind::NotFound, "no such file"));
+/// let err = result.map_err(io_add_msg!("reading {path:?}")).unwrap_err();
+///
+/// assert_eq!(err.kind(), io::ErrorKind::NotFound);
+/// assert_eq!(err.to_string(), "reading \"foo.txt\": no such file");
+/// assert_eq!(Chain::new(&err).count(), 2);
+/// ```
+#[cfg(feature = "std")]
 #[macro_export]
 macro_rules! io_add_msg {
 	($($msg:tt)+) => {
-		|err| std::io::Error::new(err.kind(), format!("{} {err}", format!($($msg)+)))
+		|err: ::std::io::Error| ::std::io::Error::new(err.kind(), $crate::error::ContextError::new(::std::format!($($msg)+), err))
+	};
+}
+
+/// Declares lazily initialized statics, optionally protected by a [Mutex](parking_lot::Mutex) or [RwLock](parking_lot::RwLock).
+///
+/// Instead of
+/// ```ignore
+/// static CACHE: Lazy<RwLock<HashMap<String, u32>>> = Lazy::new(|| RwLock::new(default()));
+/// ```
+/// You could use
+/// ```ignore
+/// global! {
+///     static CACHE: RwLock<HashMap<String, u32>>;
+/// }
+/// ```
+///
+/// The storage is chosen by the outermost type, which must be written as exactly `Mutex<T>` or `RwLock<T>` to be wrapped in a lock,
+/// any other type is stored in a plain [Lazy](once_cell::sync::Lazy).
+/// Without an initializer the value is created with [default()].
+///
+/// Accessor functions can be generated by naming them after `=>`. The first calls a closure with a reference to the value,
+/// and for locked statics an optional second one calls a closure with a mutable reference.
+///
+/// # Examples
+/// ```
+/// use nil::prelude::*;
+/// use std::collections::HashMap;
+///
+/// global! {
+///     /// Number of times each name was greeted.
+///     static GREETINGS: Mutex<HashMap<String, u32>> => with_greetings, greetings_mut;
+///     pub static GREETING: RwLock<String> = "Hello".s() => with_greeting, greeting_mut;
+///     static NAMES: Vec<&'static str> = vec!["Alice", "Bob"] => with_names;
+/// }
+///
+/// fn greet(name: &str) -> String {
+///     greetings_mut(|greetings| *greetings.entry(name.s()).or_default() += 1);
+///     with_greeting(|greeting| format!("{greeting}, {name}!"))
+/// }
+///
+/// fn main() {
+///     assert_eq!(greet("Alice"), "Hello, Alice!");
+///     greeting_mut(|greeting| *greeting = "Hi".s());
+///     assert_eq!(greet("Alice"), "Hi, Alice!");
+///
+///     assert_eq!(with_greetings(|greetings| greetings["Alice"]), 2);
+///     assert_eq!(GREETINGS.lock().len(), 1);
+///     assert_eq!(with_names(|names| names.len()), 2);
+/// }
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! global {
+	() => {};
+
+	(@init) => { $crate::default() };
+	(@init $init:expr) => { $init };
+
+	($(#[$attr:meta])* $vis:vis static $name:ident : Mutex<$ty:ty> $(= $init:expr)? $(=> $with:ident $(, $with_mut:ident)?)? ; $($rest:tt)*) => {
+		$(#[$attr])*
+		$vis static $name: $crate::once_cell::sync::Lazy<$crate::parking_lot::Mutex<$ty>> =
+			$crate::once_cell::sync::Lazy::new(|| $crate::parking_lot::Mutex::new($crate::global!(@init $($init)?)));
+		$(
+			#[doc = concat!("Locks [`", stringify!($name), "`] and calls `f` with a reference to its value.")]
+			$vis fn $with<R>(f: impl FnOnce(&$ty) -> R) -> R {
+				f(&$name.lock())
+			}
+			$(
+				#[doc = concat!("Locks [`", stringify!($name), "`] and calls `f` with a mutable reference to its value.")]
+				$vis fn $with_mut<R>(f: impl FnOnce(&mut $ty) -> R) -> R {
+					f(&mut $name.lock())
+				}
+			)?
+		)?
+		$crate::global! { $($rest)* }
+	};
+	($(#[$attr:meta])* $vis:vis static $name:ident : RwLock<$ty:ty> $(= $init:expr)? $(=> $with:ident $(, $with_mut:ident)?)? ; $($rest:tt)*) => {
+		$(#[$attr])*
+		$vis static $name: $crate::once_cell::sync::Lazy<$crate::parking_lot::RwLock<$ty>> =
+			$crate::once_cell::sync::Lazy::new(|| $crate::parking_lot::RwLock::new($crate::global!(@init $($init)?)));
+		$(
+			#[doc = concat!("Read locks [`", stringify!($name), "`] and calls `f` with a reference to its value.")]
+			$vis fn $with<R>(f: impl FnOnce(&$ty) -> R) -> R {
+				f(&$name.read())
+			}
+			$(
+				#[doc = concat!("Write locks [`", stringify!($name), "`] and calls `f` with a mutable reference to its value.")]
+				$vis fn $with_mut<R>(f: impl FnOnce(&mut $ty) -> R) -> R {
+					f(&mut $name.write())
+				}
+			)?
+		)?
+		$crate::global! { $($rest)* }
+	};
+	($(#[$attr:meta])* $vis:vis static $name:ident : $ty:ty $(= $init:expr)? $(=> $with:ident)? ; $($rest:tt)*) => {
+		$(#[$attr])*
+		$vis static $name: $crate::once_cell::sync::Lazy<$ty> = $crate::once_cell::sync::Lazy::new(|| $crate::global!(@init $($init)?));
+		$(
+			#[doc = concat!("Calls `f` with a reference to the value of [`", stringify!($name), "`].")]
+			$vis fn $with<R>(f: impl FnOnce(&$ty) -> R) -> R {
+				f(&$name)
+			}
+		)?
+		$crate::global! { $($rest)* }
+	};
+}
+
+/// Times the rest of the current scope, logging the elapsed time to stderr with `label` when it ends,
+/// and recording it to the global [profile](time::profile).
+///
+/// `label` can be anything that converts into a `Cow<'static, str>`, like a `&'static str` or a [String].
+///
+/// # Examples
+/// ```
+/// use nil::prelude::*;
+///
+/// fn build_mesh(chunk: u32) {
+///     time_scope!(format!("build_mesh {chunk}"));
+///     // ...
+/// }
+///
+/// build_mesh(1);
+/// assert_eq!(nil::time::profile().entries["build_mesh 1"].count, 1);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! time_scope {
+	($label:expr) => {
+		let _scope_timer = $crate::time::ScopeTimer::new($label);
 	};
 }
 
+/// Evaluates an expression, returning its value and how long it took as a `(value, Duration)` tuple.
+///
+/// # Examples
+/// ```
+/// use nil::prelude::*;
+/// use std::time::Duration;
+///
+/// let (value, elapsed) = timed!({
+///     std::thread::sleep(Duration::from_millis(5));
+///     5
+/// });
+/// assert_eq!(value, 5);
+/// assert!(elapsed >= Duration::from_millis(5));
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! timed {
+	($expr:expr) => {{
+		let start = ::std::time::Instant::now();
+		let value = $expr;
+		(value, start.elapsed())
+	}};
+}
+
 /// Extension trait that shortens `.to_owned()` or `.to_string_lossy().to_string()` into just `.s()` to get a [String].
 /// 
 /// # Examples
@@ -94,11 +480,43 @@ macro_rules! io_add_msg {
 /// 
 /// assert_eq!(string, owned_str);
 /// ```
+///
+/// Owned and smart pointer versions of the supported types work too, either through their own implementation or through [Deref](std::ops::Deref).
+/// ```
+/// use nil::*;
+/// use std::borrow::Cow;
+/// use std::ffi::{CString, OsString};
+/// use std::path::PathBuf;
+/// use std::rc::Rc;
+/// use std::sync::Arc;
+///
+/// assert_eq!(String::from("foo").s(), "foo");
+/// assert_eq!(Cow::Borrowed("foo").s(), "foo");
+/// assert_eq!(Box::<str>::from("foo").s(), "foo");
+/// assert_eq!(Rc::<str>::from("foo").s(), "foo");
+/// assert_eq!(Arc::<str>::from("foo").s(), "foo");
+/// assert_eq!(OsString::from("foo").s(), "foo");
+/// assert_eq!(PathBuf::from("foo").s(), "foo");
+/// assert_eq!(CString::new("foo").unwrap().s(), "foo");
+///
+/// // Byte slices are converted lossily, like `OsStr` and `Path`.
+/// assert_eq!(b"foo".s(), "foo");
+/// assert_eq!(vec![b'f', 0xFF].s(), "f\u{FFFD}");
+///
+/// // Primitives use their `Display` implementation.
+/// assert_eq!('f'.s(), "f");
+/// assert_eq!(5_u8.s(), "5");
+/// assert_eq!((-5_i64).s(), "-5");
+/// assert_eq!(1.5_f32.s(), "1.5");
+/// assert_eq!(true.s(), "true");
+/// ```
+#[cfg(feature = "alloc")]
 pub trait ShortToString {
 	/// Shorthand for getting a string representation
 	fn s(&self) -> String;
 }
 
+#[cfg(feature = "alloc")]
 impl ShortToString for str {
 	#[inline]
 	fn s(&self) -> String {
@@ -106,6 +524,7 @@ impl ShortToString for str {
 	}
 }
 
+#[cfg(feature = "std")]
 impl ShortToString for std::ffi::OsStr {
 	#[inline]
 	fn s(&self) -> String {
@@ -113,6 +532,7 @@ impl ShortToString for std::ffi::OsStr {
 	}
 }
 
+#[cfg(feature = "std")]
 impl ShortToString for std::path::Path {
 	#[inline]
 	fn s(&self) -> String {
@@ -120,13 +540,264 @@ impl ShortToString for std::path::Path {
 	}
 }
 
-impl ShortToString for std::ffi::CStr {
+#[cfg(feature = "alloc")]
+impl ShortToString for core::ffi::CStr {
 	#[inline]
 	fn s(&self) -> String {
 		self.to_string_lossy().to_string()
 	}
 }
 
+#[cfg(feature = "alloc")]
+impl ShortToString for [u8] {
+	#[inline]
+	fn s(&self) -> String {
+		String::from_utf8_lossy(self).into_owned()
+	}
+}
+
+#[cfg(feature = "alloc")]
+impl ShortToString for alloc::borrow::Cow<'_, str> {
+	#[inline]
+	fn s(&self) -> String {
+		(**self).to_owned()
+	}
+}
+
+#[cfg(feature = "alloc")]
+impl ShortToString for Box<str> {
+	#[inline]
+	fn s(&self) -> String {
+		(**self).to_owned()
+	}
+}
+
+#[cfg(feature = "alloc")]
+impl ShortToString for alloc::rc::Rc<str> {
+	#[inline]
+	fn s(&self) -> String {
+		(**self).to_owned()
+	}
+}
+
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl ShortToString for alloc::sync::Arc<str> {
+	#[inline]
+	fn s(&self) -> String {
+		(**self).to_owned()
+	}
+}
+
+#[cfg(feature = "alloc")]
+macro_rules! impl_short_to_string_with_display {
+	($($ty:ty)*) => {$(
+		impl ShortToString for $ty {
+			#[inline]
+			fn s(&self) -> String {
+				self.to_string()
+			}
+		}
+	)*};
+}
+
+#[cfg(feature = "alloc")]
+impl_short_to_string_with_display!(char bool u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize f32 f64);
+
+/// Extension trait that shortens `PathBuf::from(x)` into `.p()`, and `OsString::from(x)` into `.os()`.
+///
+/// # Examples
+/// ```
+/// use nil::*;
+/// use std::borrow::Cow;
+/// use std::ffi::{OsStr, OsString};
+/// use std::path::{Path, PathBuf};
+///
+/// let path: PathBuf = "assets".p().join("foo.png");
+/// assert_eq!(path, PathBuf::from("assets/foo.png"));
+/// assert_eq!(path.os(), OsString::from("assets/foo.png"));
+///
+/// assert_eq!("foo".s().p(), Path::new("foo"));
+/// assert_eq!(OsStr::new("foo").p(), Path::new("foo"));
+/// assert_eq!(Cow::Borrowed(Path::new("foo")).os(), OsStr::new("foo"));
+/// ```
+#[cfg(feature = "std")]
+pub trait ShortToPath {
+	/// Shorthand for getting a [PathBuf](std::path::PathBuf).
+	fn p(&self) -> std::path::PathBuf;
+	/// Shorthand for getting an [OsString](std::ffi::OsString).
+	fn os(&self) -> std::ffi::OsString;
+}
+
+#[cfg(feature = "std")]
+impl ShortToPath for str {
+	#[inline]
+	fn p(&self) -> std::path::PathBuf {
+		self.into()
+	}
+	#[inline]
+	fn os(&self) -> std::ffi::OsString {
+		self.into()
+	}
+}
+
+#[cfg(feature = "std")]
+impl ShortToPath for std::ffi::OsStr {
+	#[inline]
+	fn p(&self) -> std::path::PathBuf {
+		self.into()
+	}
+	#[inline]
+	fn os(&self) -> std::ffi::OsString {
+		self.to_owned()
+	}
+}
+
+#[cfg(feature = "std")]
+impl ShortToPath for std::path::Path {
+	#[inline]
+	fn p(&self) -> std::path::PathBuf {
+		self.to_owned()
+	}
+	#[inline]
+	fn os(&self) -> std::ffi::OsString {
+		self.as_os_str().to_owned()
+	}
+}
+
+#[cfg(feature = "std")]
+impl ShortToPath for std::borrow::Cow<'_, str> {
+	#[inline]
+	fn p(&self) -> std::path::PathBuf {
+		(**self).p()
+	}
+	#[inline]
+	fn os(&self) -> std::ffi::OsString {
+		(**self).os()
+	}
+}
+
+#[cfg(feature = "std")]
+impl ShortToPath for std::borrow::Cow<'_, std::ffi::OsStr> {
+	#[inline]
+	fn p(&self) -> std::path::PathBuf {
+		(**self).p()
+	}
+	#[inline]
+	fn os(&self) -> std::ffi::OsString {
+		(**self).os()
+	}
+}
+
+#[cfg(feature = "std")]
+impl ShortToPath for std::borrow::Cow<'_, std::path::Path> {
+	#[inline]
+	fn p(&self) -> std::path::PathBuf {
+		(**self).p()
+	}
+	#[inline]
+	fn os(&self) -> std::ffi::OsString {
+		(**self).os()
+	}
+}
+
+/// Strict counterpart to [ShortToString], `.try_s()` returns an error instead of lossily converting invalid UTF-8.
+///
+/// # Examples
+/// ```
+/// use nil::*;
+/// use std::ffi::OsStr;
+/// use std::path::Path;
+///
+/// assert_eq!(Path::new("foo.txt").try_s(), Ok("foo.txt".s()));
+///
+/// let err = b"f\xFFo".try_s().unwrap_err();
+/// assert_eq!(err, NonUtf8Error::Bytes(b"f\xFFo".to_vec()));
+/// assert_eq!(err.to_string(), "\"f\u{FFFD}o\" is not valid UTF-8");
+///
+/// # #[cfg(unix)] {
+/// use std::os::unix::ffi::OsStrExt;
+///
+/// let name = OsStr::from_bytes(b"f\xFFo");
+/// assert_eq!(name.try_s(), Err(NonUtf8Error::Os(name.to_owned())));
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub trait TryShortToString {
+	/// Shorthand for getting a string representation, failing if it would be lossy.
+	fn try_s(&self) -> Result<String, NonUtf8Error>;
+}
+
+#[cfg(feature = "std")]
+impl TryShortToString for str {
+	#[inline]
+	fn try_s(&self) -> Result<String, NonUtf8Error> {
+		Ok(self.to_owned())
+	}
+}
+
+#[cfg(feature = "std")]
+impl TryShortToString for std::ffi::OsStr {
+	#[inline]
+	fn try_s(&self) -> Result<String, NonUtf8Error> {
+		self.to_str().map(str::to_owned).ok_or_else(|| NonUtf8Error::Os(self.to_owned()))
+	}
+}
+
+#[cfg(feature = "std")]
+impl TryShortToString for std::path::Path {
+	#[inline]
+	fn try_s(&self) -> Result<String, NonUtf8Error> {
+		self.as_os_str().try_s()
+	}
+}
+
+#[cfg(feature = "std")]
+impl TryShortToString for std::ffi::CStr {
+	#[inline]
+	fn try_s(&self) -> Result<String, NonUtf8Error> {
+		self.to_bytes().try_s()
+	}
+}
+
+#[cfg(feature = "std")]
+impl TryShortToString for [u8] {
+	#[inline]
+	fn try_s(&self) -> Result<String, NonUtf8Error> {
+		std::str::from_utf8(self).map(str::to_owned).map_err(|_| NonUtf8Error::Bytes(self.to_vec()))
+	}
+}
+
+/// Returned from [TryShortToString::try_s] when the value isn't valid UTF-8, contains a copy of the original value.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NonUtf8Error {
+	/// From an [OsStr](std::ffi::OsStr) or [Path](std::path::Path).
+	Os(std::ffi::OsString),
+	/// From a [CStr](std::ffi::CStr) or byte slice.
+	Bytes(Vec<u8>),
+}
+
+#[cfg(feature = "std")]
+impl NonUtf8Error {
+	/// Lossy version of the original value, for when you want to use it anyway.
+	pub fn to_string_lossy(&self) -> std::borrow::Cow<'_, str> {
+		match self {
+			Self::Os(s) => s.to_string_lossy(),
+			Self::Bytes(bytes) => String::from_utf8_lossy(bytes),
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for NonUtf8Error {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{:?} is not valid UTF-8", self.to_string_lossy())
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NonUtf8Error {}
+
 /// Shorthand for `T::default()` or `Default::default()`, good for structure initialization. Inspired by a function of the same name and purpose in `bevy_utils`.
 #[inline]
 pub fn default<T: Default>() -> T {
@@ -0,0 +1,177 @@
+//! Small math helpers that work with both [f32] and [f64].
+//!
+//! # Examples
+//! ```
+//! use nil::math::*;
+//! use nil::math_prelude::*;
+//!
+//! assert_eq!(lerp(10.0, 20.0, 0.25), 12.5);
+//! assert_eq!(inverse_lerp(10.0, 20.0, 12.5), 0.25);
+//! assert_eq!(remap(5.0, 0.0, 10.0, 100.0, 200.0), 150.0);
+//!
+//! assert!(approx_eq(0.1 + 0.2, 0.3_f64));
+//! assert!(!approx_eq(0.1, 0.2_f32));
+//! assert!(approx_eq_eps(1.0, 1.05_f32, 0.1));
+//!
+//! assert!(approx_eq(wrap_angle(TAU + FRAC_PI_2), FRAC_PI_2));
+//! assert!(approx_eq(wrap_angle(-PI_F64 - FRAC_PI_2_F64), FRAC_PI_2_F64));
+//! assert_eq!(wrap_angle(-PI), PI);
+//! ```
+
+use core::fmt;
+use core::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+/// Implemented for [f32] and [f64] so the helpers in this module can be generic over them.
+pub trait Float:
+	Copy
+	+ Default
+	+ PartialOrd
+	+ fmt::Debug
+	+ fmt::Display
+	+ Add<Output = Self>
+	+ Sub<Output = Self>
+	+ Mul<Output = Self>
+	+ Div<Output = Self>
+	+ Rem<Output = Self>
+	+ Neg<Output = Self>
+{
+	const ZERO: Self;
+	const ONE: Self;
+	const PI: Self;
+	const TAU: Self;
+	/// Default tolerance of [approx_eq].
+	const APPROX_EPSILON: Self;
+
+	fn abs(self) -> Self;
+	fn max(self, other: Self) -> Self;
+	fn to_degrees(self) -> Self;
+	fn to_radians(self) -> Self;
+	/// Converts from an [f64], rounding if needed.
+	fn from_f64(value: f64) -> Self;
+
+	#[cfg(feature = "std")]
+	fn sin(self) -> Self;
+	#[cfg(feature = "std")]
+	fn cos(self) -> Self;
+	#[cfg(feature = "std")]
+	fn tan(self) -> Self;
+	#[cfg(feature = "std")]
+	fn atan2(self, other: Self) -> Self;
+}
+
+macro_rules! impl_float {
+	($($ty:ident $approx_epsilon:literal),*) => {$(
+		impl Float for $ty {
+			const ZERO: Self = 0.;
+			const ONE: Self = 1.;
+			const PI: Self = core::$ty::consts::PI;
+			const TAU: Self = core::$ty::consts::TAU;
+			const APPROX_EPSILON: Self = $approx_epsilon;
+
+			#[inline]
+			fn abs(self) -> Self {
+				if self < 0. { -self } else { self }
+			}
+			#[inline]
+			fn max(self, other: Self) -> Self {
+				if self < other { other } else { self }
+			}
+			#[inline]
+			fn to_degrees(self) -> Self {
+				$ty::to_degrees(self)
+			}
+			#[inline]
+			fn to_radians(self) -> Self {
+				$ty::to_radians(self)
+			}
+			#[inline]
+			fn from_f64(value: f64) -> Self {
+				value as $ty
+			}
+
+			#[cfg(feature = "std")]
+			#[inline]
+			fn sin(self) -> Self {
+				$ty::sin(self)
+			}
+			#[cfg(feature = "std")]
+			#[inline]
+			fn cos(self) -> Self {
+				$ty::cos(self)
+			}
+			#[cfg(feature = "std")]
+			#[inline]
+			fn tan(self) -> Self {
+				$ty::tan(self)
+			}
+			#[cfg(feature = "std")]
+			#[inline]
+			fn atan2(self, other: Self) -> Self {
+				$ty::atan2(self, other)
+			}
+		}
+	)*};
+}
+
+impl_float!(f32 1e-5, f64 1e-9);
+
+/// Linearly interpolates from `a` to `b` by `t`, `t` isn't clamped.
+#[inline]
+pub fn lerp<T: Float>(a: T, b: T, t: T) -> T {
+	a + (b - a) * t
+}
+
+/// Inverse of [lerp], returns how far `value` is from `a` to `b`, where `a` is 0 and `b` is 1.
+#[inline]
+pub fn inverse_lerp<T: Float>(a: T, b: T, value: T) -> T {
+	(value - a) / (b - a)
+}
+
+/// Maps `value` from the range `from_min..from_max` to `to_min..to_max`, the result isn't clamped.
+#[inline]
+pub fn remap<T: Float>(value: T, from_min: T, from_max: T, to_min: T, to_max: T) -> T {
+	lerp(to_min, to_max, inverse_lerp(from_min, from_max, value))
+}
+
+/// Returns `true` if `a` and `b` are within [Float::APPROX_EPSILON] of each other, scaled up for values bigger than 1.
+#[inline]
+pub fn approx_eq<T: Float>(a: T, b: T) -> bool {
+	approx_eq_eps(a, b, T::APPROX_EPSILON * T::ONE.max(a.abs()).max(b.abs()))
+}
+
+/// Returns `true` if `a` and `b` are within `epsilon` of each other.
+#[inline]
+pub fn approx_eq_eps<T: Float>(a: T, b: T, epsilon: T) -> bool {
+	(a - b).abs() <= epsilon
+}
+
+/// Wraps an angle in radians to the range `(-PI, PI]`.
+#[inline]
+pub fn wrap_angle<T: Float>(angle: T) -> T {
+	wrap_signed(angle, T::PI)
+}
+
+/// Wraps `value` to the range `(-half, half]`.
+#[inline]
+pub(crate) fn wrap_signed<T: Float>(value: T, half: T) -> T {
+	let full = half + half;
+	let mut wrapped = (value + half) % full;
+	if wrapped <= T::ZERO {
+		wrapped = wrapped + full;
+	}
+	wrapped - half
+}
+
+/// Wraps `value` to the range `[0, full)`.
+#[inline]
+pub(crate) fn wrap_unsigned<T: Float>(value: T, full: T) -> T {
+	let mut wrapped = value % full;
+	if wrapped < T::ZERO {
+		wrapped = wrapped + full;
+	}
+	// Adding a tiny negative number to `full` can round up to `full`.
+	if wrapped >= full {
+		wrapped = T::ZERO;
+	}
+	wrapped
+}
@@ -0,0 +1,242 @@
+//! Lexical path helpers, that work on the path itself without touching the filesystem.
+
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::path::{Component, Path, PathBuf, MAIN_SEPARATOR};
+
+/// Extension trait for [Path] (and [PathBuf] through [Deref](std::ops::Deref)) with lexical helpers.
+///
+/// None of these look at the filesystem, so they don't resolve symbolic links. A path like `link/..` normalizes to `.`,
+/// even though the filesystem would go to the parent of wherever `link` points.
+pub trait PathExt {
+	/// Collapses `.` and `..` components, and redundant separators.
+	///
+	/// `..` at the start of a relative path is kept, and `..` right after a root is removed, since a root is its own parent.
+	/// An empty result is `.`.
+	///
+	/// # Examples
+	/// ```
+	/// use nil::path::PathExt;
+	/// use std::path::Path;
+	///
+	/// let normalize = |path: &str| Path::new(path).normalize();
+	///
+	/// assert_eq!(normalize("assets/./textures/../sounds//jump.ogg"), Path::new("assets/sounds/jump.ogg"));
+	/// assert_eq!(normalize("./a/b/"), Path::new("a/b"));
+	/// assert_eq!(normalize("a/.."), Path::new("."));
+	/// assert_eq!(normalize(""), Path::new("."));
+	/// assert_eq!(normalize("."), Path::new("."));
+	/// assert_eq!(normalize("../a/../../b"), Path::new("../../b"));
+	/// assert_eq!(normalize("a/../../b"), Path::new("../b"));
+	///
+	/// // Roots stop `..`.
+	/// assert_eq!(normalize("/.."), Path::new("/"));
+	/// assert_eq!(normalize("/a/../../b"), Path::new("/b"));
+	///
+	/// if cfg!(windows) {
+	///     assert_eq!(normalize(r"C:\Games\..\..\Saves"), Path::new(r"C:\Saves"));
+	///     // Relative to the current directory of drive C, so `..` is kept.
+	///     assert_eq!(normalize(r"C:..\Saves"), Path::new(r"C:..\Saves"));
+	///     assert_eq!(normalize(r"\\server\share\a\..\.."), Path::new(r"\\server\share\"));
+	/// }
+	/// ```
+	fn normalize(&self) -> PathBuf;
+
+	/// Returns the path that gets from `base` to this path, both being [normalized](PathExt::normalize) first.
+	///
+	/// Returns [None] if there isn't one that can be worked out lexically: if only one of them is absolute,
+	/// if they have different prefixes (like different drives on windows), or if `base` goes up through `..` further than this path does.
+	///
+	/// # Examples
+	/// ```
+	/// use nil::path::PathExt;
+	/// use std::path::Path;
+	///
+	/// let relative = |path: &str, base: &str| Path::new(path).relative_to(base);
+	///
+	/// assert_eq!(relative("/game/assets/player.png", "/game"), Some("assets/player.png".into()));
+	/// assert_eq!(relative("/game/assets/player.png", "/game/saves/slot1"), Some("../../assets/player.png".into()));
+	/// assert_eq!(relative("/game", "/game/assets"), Some("..".into()));
+	/// assert_eq!(relative("/game", "/game/"), Some(".".into()));
+	/// assert_eq!(relative("/", "/game"), Some("..".into()));
+	/// assert_eq!(relative("/game", "/"), Some("game".into()));
+	///
+	/// assert_eq!(relative("src/lib.rs", "."), Some("src/lib.rs".into()));
+	/// assert_eq!(relative("src/../Cargo.toml", "src/fs"), Some("../../Cargo.toml".into()));
+	/// assert_eq!(relative("../shared", "app"), Some("../../shared".into()));
+	///
+	/// // Not knowable without the current directory.
+	/// assert_eq!(relative("app", "../shared"), None);
+	/// assert_eq!(relative("/game", "game"), None);
+	/// assert_eq!(relative("game", "/game"), None);
+	///
+	/// if cfg!(windows) {
+	///     assert_eq!(relative(r"C:\Games", r"D:\Games"), None);
+	///     assert_eq!(relative(r"C:\Games\Saves", r"C:\Games"), Some("Saves".into()));
+	/// }
+	/// ```
+	fn relative_to(&self, base: impl AsRef<Path>) -> Option<PathBuf>;
+
+	/// Returns `true` if this path is `base` or inside of it, after both are [normalized](PathExt::normalize).
+	///
+	/// Useful for making sure a path from a user doesn't escape a directory with `..`. Keep in mind it's lexical, so symbolic links inside `base` can still lead outside of it.
+	///
+	/// # Examples
+	/// ```
+	/// use nil::path::PathExt;
+	/// use std::path::Path;
+	///
+	/// let mods = Path::new("/game/mods");
+	/// let within = |user_path: &str| mods.join(user_path).is_within(mods);
+	///
+	/// assert!(within("cool_mod/mod.toml"));
+	/// assert!(within("cool_mod/../other_mod"));
+	/// assert!(within("."));
+	/// assert!(!within(".."));
+	/// assert!(!within("../mods_backup"));
+	/// assert!(!within("cool_mod/../../saves"));
+	/// // Joining an absolute path replaces the base.
+	/// assert!(!within("/etc/passwd"));
+	///
+	/// // Whole components are compared, not strings.
+	/// assert!(!Path::new("/game/mods2").is_within(mods));
+	///
+	/// assert!(Path::new("a/b").is_within("."));
+	/// assert!(!Path::new("../a").is_within("."));
+	/// assert!(Path::new("../a").is_within(".."));
+	/// assert!(!Path::new("a").is_within("/"));
+	/// assert!(!Path::new("/etc/passwd").is_within("."));
+	/// assert!(!Path::new("/etc/passwd").is_within(""));
+	/// assert!(!Path::new("/etc/passwd").is_within("a/.."));
+	/// assert!(Path::new("/a").is_within("/"));
+	/// ```
+	fn is_within(&self, base: impl AsRef<Path>) -> bool;
+
+	/// Adds `extension` after any existing one, unlike [Path::with_extension] which replaces it. A leading `.` on `extension` is ignored.
+	///
+	/// Does nothing if the path has no file name, or `extension` is empty.
+	///
+	/// # Examples
+	/// ```
+	/// use nil::path::PathExt;
+	/// use std::path::Path;
+	///
+	/// assert_eq!(Path::new("save.json").with_extra_extension("bak"), Path::new("save.json.bak"));
+	/// assert_eq!(Path::new("assets.tar").with_extra_extension(".gz"), Path::new("assets.tar.gz"));
+	/// assert_eq!(Path::new("saves/slot1").with_extra_extension("sav"), Path::new("saves/slot1.sav"));
+	/// assert_eq!(Path::new(".config").with_extra_extension("old"), Path::new(".config.old"));
+	/// assert_eq!(Path::new("save.json").with_extra_extension(""), Path::new("save.json"));
+	/// assert_eq!(Path::new("/").with_extra_extension("bak"), Path::new("/"));
+	/// assert_eq!(Path::new("saves/..").with_extra_extension("bak"), Path::new("saves/.."));
+	/// ```
+	fn with_extra_extension(&self, extension: impl AsRef<OsStr>) -> PathBuf;
+
+	/// Returns the path as a string with `/` as the separator on every platform, replacing invalid unicode like [Path::to_string_lossy].
+	///
+	/// # Examples
+	/// ```
+	/// use nil::path::PathExt;
+	/// use std::path::Path;
+	///
+	/// let path = Path::new("assets").join("textures").join("grass.png");
+	/// assert_eq!(path.to_slash(), "assets/textures/grass.png");
+	/// assert_eq!(Path::new("/usr/share").to_slash(), "/usr/share");
+	///
+	/// if cfg!(windows) {
+	///     assert_eq!(Path::new(r"C:\Games\nil").to_slash(), "C:/Games/nil");
+	/// }
+	/// ```
+	fn to_slash(&self) -> Cow<'_, str>;
+}
+
+impl PathExt for Path {
+	fn normalize(&self) -> PathBuf {
+		let components = normal_components(self);
+		if components.is_empty() {
+			return PathBuf::from(".");
+		}
+		components.iter().collect()
+	}
+
+	fn relative_to(&self, base: impl AsRef<Path>) -> Option<PathBuf> {
+		let path = normal_components(self);
+		let base = normal_components(base.as_ref());
+
+		let common = path.iter().zip(&base).take_while(|(a, b)| a == b).count();
+		let (path, base) = (&path[common..], &base[common..]);
+
+		if !base.iter().all(|component| matches!(component, Component::Normal(_))) {
+			return None;
+		}
+		if path.iter().any(|component| matches!(component, Component::Prefix(_) | Component::RootDir)) {
+			return None;
+		}
+
+		let relative: PathBuf = base.iter().map(|_| Component::ParentDir).chain(path.iter().copied()).collect();
+		if relative.as_os_str().is_empty() {
+			return Some(PathBuf::from("."));
+		}
+		Some(relative)
+	}
+
+	fn is_within(&self, base: impl AsRef<Path>) -> bool {
+		let path = normal_components(self);
+		let base = normal_components(base.as_ref());
+
+		// A relative base has no components to compare when it normalizes to `.`, so absolute paths have to be ruled out separately.
+		let anchored = |components: &[Component]| {
+			components.first().is_some_and(|component| matches!(component, Component::Prefix(_) | Component::RootDir))
+		};
+		if anchored(&path) != anchored(&base) {
+			return false;
+		}
+
+		path.starts_with(&base) && !path[base.len()..].contains(&Component::ParentDir)
+	}
+
+	fn with_extra_extension(&self, extension: impl AsRef<OsStr>) -> PathBuf {
+		let extension = extension.as_ref();
+		let has_dot = extension.as_encoded_bytes().starts_with(b".");
+
+		let Some(file_name) = self.file_name() else { return self.to_path_buf() };
+		if extension.is_empty() || extension == "." {
+			return self.to_path_buf();
+		}
+
+		let mut file_name = file_name.to_os_string();
+		if !has_dot {
+			file_name.push(".");
+		}
+		file_name.push(extension);
+		self.with_file_name(file_name)
+	}
+
+	fn to_slash(&self) -> Cow<'_, str> {
+		let string = self.to_string_lossy();
+		if MAIN_SEPARATOR == '/' {
+			return string;
+		}
+		Cow::Owned(string.replace(MAIN_SEPARATOR, "/"))
+	}
+}
+
+/// Components of `path` with `.` removed and `..` collapsed where possible. Any `..` left is at the start, after the prefix if there is one.
+fn normal_components(path: &Path) -> Vec<Component<'_>> {
+	let mut components = Vec::new();
+
+	for component in path.components() {
+		match component {
+			Component::CurDir => {}
+			Component::ParentDir => match components.last() {
+				Some(Component::Normal(_)) => {
+					components.pop();
+				}
+				Some(Component::RootDir) => {}
+				_ => components.push(component),
+			},
+			_ => components.push(component),
+		}
+	}
+
+	components
+}
@@ -0,0 +1,108 @@
+//! Pretty error reporting for `main` and tests.
+
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::error::Error;
+use std::fmt;
+
+use crate::error::Chain;
+
+/// An error that prints itself along with its chain of [sources](Error::source) (and a backtrace if one was captured) when debug formatted.
+///
+/// This makes it a good fit for returning from `main` or tests, since those debug format the returned error.
+/// Any error can be converted into a [Report] with `?`.
+///
+/// Messages added with [io_add_msg!](crate::io_add_msg) or [IoResultExt](crate::error::IoResultExt) show up as their own levels of the chain.
+///
+/// A backtrace is captured when the report is created if enabled through the `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` environment variables,
+/// see [Backtrace::capture].
+///
+/// # Examples
+/// ```
+/// use nil::prelude::*;
+/// use std::io;
+///
+/// fn load() -> Result<(), Report> {
+///     let result: io::Result<()> = Err(io::Error::new(io::ErrorKind::NotFound, "no such file"));
+///     result.map_err(io_add_msg!("reading \"level.map\""))?;
+///     Ok(())
+/// }
+///
+/// let report = load().unwrap_err();
+///
+/// assert!(format!("{report:?}").starts_with("reading \"level.map\"\n\nCaused by:\n    no such file"));
+/// ```
+pub struct Report {
+	error: Box<dyn Error + Send + Sync>,
+	backtrace: Backtrace,
+}
+
+impl Report {
+	pub fn new(error: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+		Self { error: error.into(), backtrace: Backtrace::capture() }
+	}
+
+	/// The error this report was created from.
+	#[inline]
+	pub fn error(&self) -> &(dyn Error + Send + Sync + 'static) {
+		&*self.error
+	}
+
+	#[inline]
+	pub fn into_error(self) -> Box<dyn Error + Send + Sync> {
+		self.error
+	}
+
+	/// The backtrace captured when this report was created, check [Backtrace::status] to see if it was actually captured.
+	#[inline]
+	pub fn backtrace(&self) -> &Backtrace {
+		&self.backtrace
+	}
+
+	/// Iterates over the error and all of its sources.
+	#[inline]
+	pub fn chain(&self) -> Chain<'_> {
+		Chain::new(&*self.error)
+	}
+}
+
+impl<E: Error + Send + Sync + 'static> From<E> for Report {
+	#[inline]
+	fn from(error: E) -> Self {
+		Self::new(error)
+	}
+}
+
+impl fmt::Display for Report {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(&self.error, f)
+	}
+}
+
+impl fmt::Debug for Report {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&crate::error::own_msg(&*self.error))?;
+
+		let causes: Vec<_> = self.chain().skip(1).collect();
+
+		if !causes.is_empty() {
+			f.write_str("\n\nCaused by:")?;
+
+			for (i, cause) in causes.iter().enumerate() {
+				f.write_str("\n    ")?;
+				let indent = if causes.len() > 1 {
+					write!(f, "{i}: ")?;
+					"\n       "
+				} else {
+					"\n    "
+				};
+				f.write_str(&crate::error::own_msg(*cause).replace('\n', indent))?;
+			}
+		}
+
+		if self.backtrace.status() == BacktraceStatus::Captured {
+			write!(f, "\n\nStack backtrace:\n{}", self.backtrace)?;
+		}
+
+		Ok(())
+	}
+}
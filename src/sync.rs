@@ -0,0 +1,9 @@
+//! Synchronization helpers built on the [parking_lot] re-export.
+
+crate::flat! {
+	shared;
+	ordered;
+	traced;
+	#[cfg(feature = "deadlock_detection")]
+	deadlock;
+}
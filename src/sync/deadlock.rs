@@ -0,0 +1,113 @@
+use std::fmt;
+use std::thread::{self, JoinHandle, ThreadId};
+use std::time::Duration;
+
+use parking_lot::deadlock::check_deadlock;
+
+/// A cycle of threads waiting on each other, found by [spawn_deadlock_watchdog].
+#[derive(Debug, Clone)]
+pub struct Deadlock {
+	pub threads: Vec<DeadlockedThread>,
+}
+
+/// A thread that's part of a [Deadlock].
+#[derive(Debug, Clone)]
+pub struct DeadlockedThread {
+	pub thread_id: ThreadId,
+	/// Formatted backtrace of where the thread is blocked.
+	pub backtrace: String,
+}
+
+impl fmt::Display for Deadlock {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "deadlock between {} threads", self.threads.len())?;
+
+		for thread in &self.threads {
+			write!(f, "\n\nThread {:?}:\n{}", thread.thread_id, thread.backtrace)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Spawns a thread that checks for deadlocks every `interval`, printing any it finds to stderr.
+///
+/// Requires the `deadlock_detection` feature, which enables parking_lot's deadlock detection.
+/// Only locks from [parking_lot] take part in detection, which includes everything in this module and the prelude.
+///
+/// # Examples
+/// ```no_run
+/// use nil::sync::spawn_deadlock_watchdog;
+/// use std::time::Duration;
+///
+/// spawn_deadlock_watchdog(Duration::from_secs(10));
+/// ```
+pub fn spawn_deadlock_watchdog(interval: Duration) -> JoinHandle<()> {
+	spawn_deadlock_watchdog_with(interval, |deadlocks| {
+		for deadlock in deadlocks {
+			eprintln!("{deadlock}");
+		}
+	})
+}
+
+/// Spawns a thread that checks for deadlocks every `interval`, calling `on_deadlock` with any deadlocks found since the last check.
+///
+/// The thread runs for the rest of the program.
+///
+/// # Examples
+/// ```
+/// use nil::prelude::*;
+/// use nil::sync::spawn_deadlock_watchdog_with;
+/// use std::sync::{mpsc, Arc, Barrier};
+/// use std::thread;
+/// use std::time::Duration;
+///
+/// let (sender, receiver) = mpsc::channel();
+/// spawn_deadlock_watchdog_with(Duration::from_millis(50), move |deadlocks| {
+///     for deadlock in deadlocks {
+///         let ids: Vec<_> = deadlock.threads.iter().map(|thread| thread.thread_id).collect();
+///         let _ = sender.send(ids);
+///     }
+/// });
+///
+/// // Two threads that each lock one mutex, then wait for the other one's.
+/// let (a, b) = (Arc::new(Mutex::new(())), Arc::new(Mutex::new(())));
+/// let barrier = Arc::new(Barrier::new(2));
+/// let spawn = |first: Arc<Mutex<()>>, second: Arc<Mutex<()>>, barrier: Arc<Barrier>| {
+///     thread::spawn(move || {
+///         let _first = first.lock();
+///         barrier.wait();
+///         let _second = second.lock();
+///     })
+/// };
+/// let threads = [spawn(a.clone(), b.clone(), barrier.clone()), spawn(b, a, barrier)];
+///
+/// let ids = receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+/// assert_eq!(ids.len(), 2);
+/// assert!(threads.iter().all(|thread| ids.contains(&thread.thread().id())));
+/// ```
+pub fn spawn_deadlock_watchdog_with(interval: Duration, on_deadlock: impl Fn(&[Deadlock]) + Send + 'static) -> JoinHandle<()> {
+	thread::Builder::new()
+		.name("deadlock watchdog".into())
+		.spawn(move || loop {
+			thread::sleep(interval);
+
+			let deadlocks: Vec<Deadlock> = check_deadlock()
+				.into_iter()
+				.map(|threads| Deadlock {
+					threads: threads
+						.into_iter()
+						.map(|thread| DeadlockedThread {
+							thread_id: thread.thread_id(),
+							backtrace: format!("{:?}", thread.backtrace()),
+						})
+						.collect(),
+				})
+				.collect();
+
+			if !deadlocks.is_empty() {
+				on_deadlock(&deadlocks);
+			}
+		})
+		.expect("failed to spawn deadlock watchdog thread")
+}
@@ -0,0 +1,467 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::{Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+#[cfg(debug_assertions)]
+use std::cell::RefCell;
+#[cfg(debug_assertions)]
+use std::collections::{HashMap, HashSet};
+#[cfg(debug_assertions)]
+use std::ops::{Deref, DerefMut};
+#[cfg(debug_assertions)]
+use once_cell::sync::Lazy;
+
+/// A [Mutex] that, in debug builds, panics when locks are acquired in an order that contradicts one seen before,
+/// catching potential deadlocks even if they never actually happen. In release builds this is just a [Mutex].
+///
+/// Locks are identified by their name, locks with the same name aren't checked against each other.
+/// Locks with a level, made with [with_level](Self::with_level), additionally have to be acquired in increasing level order.
+///
+/// Only blocking acquisitions are checked, since `try_*` methods can't deadlock. They still count as held for any locks acquired after them.
+///
+/// Violations panic by default, use [set_lock_order_handler] to report them some other way.
+///
+/// # Examples
+/// ```
+/// use nil::prelude::*;
+///
+/// static PLAYERS: OrderedMutex<Vec<&str>> = OrderedMutex::new("players", Vec::new());
+/// static SCORES: OrderedMutex<Vec<u32>> = OrderedMutex::new("scores", Vec::new());
+///
+/// {
+///     let mut players = PLAYERS.lock();
+///     let mut scores = SCORES.lock();
+///     players.push("nox");
+///     scores.push(0);
+/// }
+///
+/// // Panics in debug builds, as another thread doing this could deadlock with the code above.
+/// let result = std::panic::catch_unwind(|| {
+///     let _scores = SCORES.lock();
+///     let _players = PLAYERS.lock();
+/// });
+/// assert_eq!(result.is_err(), nil::sync::LOCK_ORDER_CHECKS);
+///
+/// // Fine, locks with the same name aren't checked against each other, even with a level.
+/// let parent = OrderedMutex::with_level("node", 1, ());
+/// let child = OrderedMutex::with_level("node", 1, ());
+/// let _parent = parent.lock();
+/// let _child = child.lock();
+/// ```
+pub struct OrderedMutex<T: ?Sized> {
+	#[cfg(debug_assertions)]
+	class: LockClass,
+	inner: Mutex<T>,
+}
+
+/// A [RwLock] that, in debug builds, checks the order locks are acquired in. See [OrderedMutex] for details.
+///
+/// Reads and writes are checked the same way, as a read can still block behind a waiting writer.
+pub struct OrderedRwLock<T: ?Sized> {
+	#[cfg(debug_assertions)]
+	class: LockClass,
+	inner: RwLock<T>,
+}
+
+#[cfg(debug_assertions)]
+pub type OrderedMutexGuard<'a, T> = OrderedGuard<MutexGuard<'a, T>>;
+#[cfg(not(debug_assertions))]
+pub type OrderedMutexGuard<'a, T> = MutexGuard<'a, T>;
+
+#[cfg(debug_assertions)]
+pub type OrderedRwLockReadGuard<'a, T> = OrderedGuard<RwLockReadGuard<'a, T>>;
+#[cfg(not(debug_assertions))]
+pub type OrderedRwLockReadGuard<'a, T> = RwLockReadGuard<'a, T>;
+
+#[cfg(debug_assertions)]
+pub type OrderedRwLockWriteGuard<'a, T> = OrderedGuard<RwLockWriteGuard<'a, T>>;
+#[cfg(not(debug_assertions))]
+pub type OrderedRwLockWriteGuard<'a, T> = RwLockWriteGuard<'a, T>;
+
+/// Removes its lock from the thread's held locks when dropped.
+#[cfg(debug_assertions)]
+pub struct OrderedGuard<G> {
+	guard: G,
+	class: LockClass,
+}
+
+#[cfg(debug_assertions)]
+impl<G: Deref> Deref for OrderedGuard<G> {
+	type Target = G::Target;
+	#[inline]
+	fn deref(&self) -> &Self::Target {
+		&self.guard
+	}
+}
+
+#[cfg(debug_assertions)]
+impl<G: DerefMut> DerefMut for OrderedGuard<G> {
+	#[inline]
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.guard
+	}
+}
+
+#[cfg(debug_assertions)]
+impl<G> Drop for OrderedGuard<G> {
+	fn drop(&mut self) {
+		// Can fail if the guard is dropped while the thread is being torn down.
+		let _ = HELD.try_with(|held| {
+			let mut held = held.borrow_mut();
+			if let Some(i) = held.iter().rposition(|class| *class == self.class) {
+				held.remove(i);
+			}
+		});
+	}
+}
+
+#[cfg(debug_assertions)]
+impl<G: fmt::Debug> fmt::Debug for OrderedGuard<G> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.guard.fmt(f)
+	}
+}
+
+/// Checks the order before blocking on `lock`.
+#[cfg(debug_assertions)]
+#[inline]
+fn acquire<G>(class: LockClass, lock: impl FnOnce() -> G) -> OrderedGuard<G> {
+	check_order(class);
+	hold(class, lock())
+}
+
+#[cfg(debug_assertions)]
+#[inline]
+fn try_acquire<G>(class: LockClass, try_lock: impl FnOnce() -> Option<G>) -> Option<OrderedGuard<G>> {
+	try_lock().map(|guard| hold(class, guard))
+}
+
+#[cfg(debug_assertions)]
+fn hold<G>(class: LockClass, guard: G) -> OrderedGuard<G> {
+	HELD.with(|held| held.borrow_mut().push(class));
+	OrderedGuard { guard, class }
+}
+
+macro_rules! ordered {
+	($lock:expr, $lock_fn:ident) => {{
+		#[cfg(debug_assertions)]
+		{
+			acquire($lock.class, || $lock.inner.$lock_fn())
+		}
+		#[cfg(not(debug_assertions))]
+		{
+			$lock.inner.$lock_fn()
+		}
+	}};
+}
+
+macro_rules! try_ordered {
+	($lock:expr, $lock_fn:ident $(, $timeout:ident)?) => {{
+		#[cfg(debug_assertions)]
+		{
+			try_acquire($lock.class, || $lock.inner.$lock_fn($($timeout)?))
+		}
+		#[cfg(not(debug_assertions))]
+		{
+			$lock.inner.$lock_fn($($timeout)?)
+		}
+	}};
+}
+
+impl<T> OrderedMutex<T> {
+	/// Creates a new mutex ordered by `name`.
+	#[inline]
+	pub const fn new(name: &'static str, value: T) -> Self {
+		#[cfg(not(debug_assertions))]
+		let _ = name;
+		Self {
+			#[cfg(debug_assertions)]
+			class: LockClass { name, level: None },
+			inner: Mutex::new(value),
+		}
+	}
+
+	/// Creates a new mutex ordered by `name` that can only be locked while every held lock with a level has a lower level than `level`.
+	#[inline]
+	pub const fn with_level(name: &'static str, level: u32, value: T) -> Self {
+		#[cfg(not(debug_assertions))]
+		let _ = (name, level);
+		Self {
+			#[cfg(debug_assertions)]
+			class: LockClass { name, level: Some(level) },
+			inner: Mutex::new(value),
+		}
+	}
+
+	#[inline]
+	pub fn into_inner(self) -> T {
+		self.inner.into_inner()
+	}
+}
+
+impl<T: ?Sized> OrderedMutex<T> {
+	#[inline]
+	pub fn lock(&self) -> OrderedMutexGuard<'_, T> {
+		ordered!(self, lock)
+	}
+
+	#[inline]
+	pub fn try_lock(&self) -> Option<OrderedMutexGuard<'_, T>> {
+		try_ordered!(self, try_lock)
+	}
+
+	#[inline]
+	pub fn try_lock_for(&self, timeout: Duration) -> Option<OrderedMutexGuard<'_, T>> {
+		try_ordered!(self, try_lock_for, timeout)
+	}
+
+	#[inline]
+	pub fn is_locked(&self) -> bool {
+		self.inner.is_locked()
+	}
+
+	#[inline]
+	pub fn get_mut(&mut self) -> &mut T {
+		self.inner.get_mut()
+	}
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for OrderedMutex<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.inner.fmt(f)
+	}
+}
+
+impl<T> OrderedRwLock<T> {
+	/// Creates a new lock ordered by `name`.
+	#[inline]
+	pub const fn new(name: &'static str, value: T) -> Self {
+		#[cfg(not(debug_assertions))]
+		let _ = name;
+		Self {
+			#[cfg(debug_assertions)]
+			class: LockClass { name, level: None },
+			inner: RwLock::new(value),
+		}
+	}
+
+	/// Creates a new lock ordered by `name` that can only be locked while every held lock with a level has a lower level than `level`.
+	#[inline]
+	pub const fn with_level(name: &'static str, level: u32, value: T) -> Self {
+		#[cfg(not(debug_assertions))]
+		let _ = (name, level);
+		Self {
+			#[cfg(debug_assertions)]
+			class: LockClass { name, level: Some(level) },
+			inner: RwLock::new(value),
+		}
+	}
+
+	#[inline]
+	pub fn into_inner(self) -> T {
+		self.inner.into_inner()
+	}
+}
+
+impl<T: ?Sized> OrderedRwLock<T> {
+	#[inline]
+	pub fn read(&self) -> OrderedRwLockReadGuard<'_, T> {
+		ordered!(self, read)
+	}
+
+	#[inline]
+	pub fn write(&self) -> OrderedRwLockWriteGuard<'_, T> {
+		ordered!(self, write)
+	}
+
+	#[inline]
+	pub fn try_read(&self) -> Option<OrderedRwLockReadGuard<'_, T>> {
+		try_ordered!(self, try_read)
+	}
+
+	#[inline]
+	pub fn try_write(&self) -> Option<OrderedRwLockWriteGuard<'_, T>> {
+		try_ordered!(self, try_write)
+	}
+
+	#[inline]
+	pub fn try_read_for(&self, timeout: Duration) -> Option<OrderedRwLockReadGuard<'_, T>> {
+		try_ordered!(self, try_read_for, timeout)
+	}
+
+	#[inline]
+	pub fn try_write_for(&self, timeout: Duration) -> Option<OrderedRwLockWriteGuard<'_, T>> {
+		try_ordered!(self, try_write_for, timeout)
+	}
+
+	#[inline]
+	pub fn is_locked(&self) -> bool {
+		self.inner.is_locked()
+	}
+
+	#[inline]
+	pub fn get_mut(&mut self) -> &mut T {
+		self.inner.get_mut()
+	}
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for OrderedRwLock<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.inner.fmt(f)
+	}
+}
+
+#[cfg(debug_assertions)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct LockClass {
+	name: &'static str,
+	level: Option<u32>,
+}
+
+#[cfg(debug_assertions)]
+thread_local! {
+	/// Locks held by the current thread, in the order they were acquired.
+	static HELD: RefCell<Vec<LockClass>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Every order locks have been acquired in, as edges from a held lock to the locks acquired while holding it.
+#[cfg(debug_assertions)]
+static LOCK_ORDER: Lazy<Mutex<HashMap<&'static str, HashSet<&'static str>>>> = Lazy::new(Default::default);
+
+#[cfg(debug_assertions)]
+fn check_order(locking: LockClass) {
+	let violation = HELD.with(|held| {
+		let held = held.borrow();
+		if held.is_empty() {
+			return None;
+		}
+
+		for held in held.iter().filter(|held| held.name != locking.name) {
+			if let (Some(held_level), Some(locking_level)) = (held.level, locking.level) {
+				if held_level >= locking_level {
+					return Some(LockOrderViolation {
+						locking: locking.name,
+						held: held.name,
+						kind: LockOrderViolationKind::Level { locking: locking_level, held: held_level },
+					});
+				}
+			}
+		}
+
+		let mut order = LOCK_ORDER.lock();
+		for held in held.iter().filter(|held| held.name != locking.name) {
+			if order.get(held.name).is_some_and(|after| after.contains(locking.name)) {
+				continue;
+			}
+			if let Some(previous) = find_order(&order, locking.name, held.name) {
+				return Some(LockOrderViolation { locking: locking.name, held: held.name, kind: LockOrderViolationKind::Order { previous } });
+			}
+			order.entry(held.name).or_default().insert(locking.name);
+		}
+
+		None
+	});
+
+	if let Some(violation) = violation {
+		let handler = HANDLER.read().clone();
+		match handler {
+			Some(handler) => handler(&violation),
+			None => panic!("{violation}"),
+		}
+	}
+}
+
+/// Searches for a chain of locks that have been acquired in order from `from` to `to`, including both.
+#[cfg(debug_assertions)]
+fn find_order(order: &HashMap<&'static str, HashSet<&'static str>>, from: &'static str, to: &'static str) -> Option<Vec<&'static str>> {
+	let mut came_from: HashMap<&'static str, &'static str> = HashMap::new();
+	let mut stack = vec![from];
+
+	while let Some(name) = stack.pop() {
+		if name == to {
+			let mut path = vec![to];
+			let mut current = to;
+			while let Some(&previous) = came_from.get(current) {
+				path.push(previous);
+				current = previous;
+			}
+			path.reverse();
+			return Some(path);
+		}
+
+		for &next in order.get(name).into_iter().flatten() {
+			if next != from && !came_from.contains_key(next) {
+				came_from.insert(next, name);
+				stack.push(next);
+			}
+		}
+	}
+
+	None
+}
+
+/// Whether [OrderedMutex] and [OrderedRwLock] check the order they're acquired in, which is only when nil is built with debug assertions.
+pub const LOCK_ORDER_CHECKS: bool = cfg!(debug_assertions);
+
+type LockOrderHandler = Arc<dyn Fn(&LockOrderViolation) + Send + Sync>;
+
+static HANDLER: RwLock<Option<LockOrderHandler>> = RwLock::new(None);
+
+/// Replaces the default handler that panics when [OrderedMutex] or [OrderedRwLock] are acquired in the wrong order.
+///
+/// The handler is called on the thread acquiring the lock, before it blocks. Never called in release builds.
+///
+/// # Examples
+/// ```
+/// nil::sync::set_lock_order_handler(|violation| eprintln!("{violation}"));
+/// ```
+pub fn set_lock_order_handler(handler: impl Fn(&LockOrderViolation) + Send + Sync + 'static) {
+	*HANDLER.write() = Some(Arc::new(handler));
+}
+
+/// Goes back to panicking when locks are acquired in the wrong order.
+pub fn reset_lock_order_handler() {
+	*HANDLER.write() = None;
+}
+
+/// A lock was acquired in an order that could deadlock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockOrderViolation {
+	/// Name of the lock being acquired.
+	pub locking: &'static str,
+	/// Name of the held lock it conflicts with.
+	pub held: &'static str,
+	pub kind: LockOrderViolationKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockOrderViolationKind {
+	/// The held lock has a level higher or equal to the one being acquired.
+	Level { locking: u32, held: u32 },
+	/// The locks have been acquired the other way around before, `previous` is the chain of locks that were, from `locking` to `held`.
+	Order { previous: Vec<&'static str> },
+}
+
+impl fmt::Display for LockOrderViolation {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "lock order violation: locking {:?} while holding {:?}", self.locking, self.held)?;
+
+		match &self.kind {
+			LockOrderViolationKind::Level { locking, held } => write!(f, ", but its level {locking} isn't above {held}"),
+			LockOrderViolationKind::Order { previous } => {
+				f.write_str(", but they were locked in the order ")?;
+				for (i, name) in previous.iter().enumerate() {
+					if i > 0 {
+						f.write_str(" -> ")?;
+					}
+					write!(f, "{name:?}")?;
+				}
+				f.write_str(" before")
+			}
+		}
+	}
+}
+
+impl std::error::Error for LockOrderViolation {}
@@ -0,0 +1,273 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::{ArcMutexGuard, ArcRwLockReadGuard, ArcRwLockWriteGuard, Mutex, MutexGuard, RawMutex, RawRwLock, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Shorthand for `Arc<Mutex<T>>`, cloning it gives another handle to the same value.
+///
+/// # Examples
+/// ```
+/// use nil::prelude::*;
+/// use std::thread;
+///
+/// let counter = Shared::new(0);
+///
+/// let handles: Vec<_> = (0..4).map(|_| {
+///     let counter = counter.clone();
+///     thread::spawn(move || counter.with(|count| *count += 1))
+/// }).collect();
+///
+/// for handle in handles {
+///     handle.join().unwrap();
+/// }
+///
+/// assert_eq!(*counter.lock(), 4);
+///
+/// // Owned guards keep the value alive, and can be moved between threads.
+/// let guard = counter.lock_arc();
+/// drop(counter);
+/// assert_eq!(*guard, 4);
+/// ```
+pub struct Shared<T: ?Sized>(Arc<Mutex<T>>);
+
+impl<T> Shared<T> {
+	#[inline]
+	pub fn new(value: T) -> Self {
+		Self(Arc::new(Mutex::new(value)))
+	}
+}
+
+impl<T: ?Sized> Shared<T> {
+	#[inline]
+	pub fn lock(&self) -> MutexGuard<'_, T> {
+		self.0.lock()
+	}
+
+	#[inline]
+	pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+		self.0.try_lock()
+	}
+
+	#[inline]
+	pub fn try_lock_for(&self, timeout: Duration) -> Option<MutexGuard<'_, T>> {
+		self.0.try_lock_for(timeout)
+	}
+
+	/// Like [lock](Self::lock), but the guard holds a reference to the [Arc] instead of borrowing this handle.
+	#[inline]
+	pub fn lock_arc(&self) -> ArcMutexGuard<RawMutex, T> {
+		self.0.lock_arc()
+	}
+
+	#[inline]
+	pub fn try_lock_arc(&self) -> Option<ArcMutexGuard<RawMutex, T>> {
+		self.0.try_lock_arc()
+	}
+
+	#[inline]
+	pub fn try_lock_arc_for(&self, timeout: Duration) -> Option<ArcMutexGuard<RawMutex, T>> {
+		self.0.try_lock_arc_for(timeout)
+	}
+
+	/// Locks the value for the duration of `f`.
+	#[inline]
+	pub fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+		f(&mut self.0.lock())
+	}
+
+	/// Returns `true` if both handles point to the same value.
+	#[inline]
+	pub fn ptr_eq(&self, other: &Self) -> bool {
+		Arc::ptr_eq(&self.0, &other.0)
+	}
+
+	#[inline]
+	pub fn as_arc(&self) -> &Arc<Mutex<T>> {
+		&self.0
+	}
+
+	#[inline]
+	pub fn into_arc(self) -> Arc<Mutex<T>> {
+		self.0
+	}
+}
+
+impl<T: ?Sized> Clone for Shared<T> {
+	#[inline]
+	fn clone(&self) -> Self {
+		Self(self.0.clone())
+	}
+}
+
+impl<T: Default> Default for Shared<T> {
+	#[inline]
+	fn default() -> Self {
+		Self::new(crate::default())
+	}
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Shared<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("Shared").field(&self.0).finish()
+	}
+}
+
+impl<T> From<T> for Shared<T> {
+	#[inline]
+	fn from(value: T) -> Self {
+		Self::new(value)
+	}
+}
+
+impl<T: ?Sized> From<Arc<Mutex<T>>> for Shared<T> {
+	#[inline]
+	fn from(value: Arc<Mutex<T>>) -> Self {
+		Self(value)
+	}
+}
+
+/// Shorthand for `Arc<RwLock<T>>`, cloning it gives another handle to the same value.
+///
+/// # Examples
+/// ```
+/// use nil::prelude::*;
+/// use std::time::Duration;
+///
+/// let settings = SharedRw::new(vec!["fullscreen"]);
+/// let handle = settings.clone();
+///
+/// handle.with_mut(|settings| settings.push("vsync"));
+///
+/// assert_eq!(settings.with(|settings| settings.len()), 2);
+/// assert!(settings.ptr_eq(&handle));
+///
+/// let read = settings.read();
+/// assert!(handle.try_write_for(Duration::from_millis(1)).is_none());
+/// drop(read);
+/// assert!(handle.try_write_for(Duration::from_millis(1)).is_some());
+/// ```
+pub struct SharedRw<T: ?Sized>(Arc<RwLock<T>>);
+
+impl<T> SharedRw<T> {
+	#[inline]
+	pub fn new(value: T) -> Self {
+		Self(Arc::new(RwLock::new(value)))
+	}
+}
+
+impl<T: ?Sized> SharedRw<T> {
+	#[inline]
+	pub fn read(&self) -> RwLockReadGuard<'_, T> {
+		self.0.read()
+	}
+
+	#[inline]
+	pub fn write(&self) -> RwLockWriteGuard<'_, T> {
+		self.0.write()
+	}
+
+	#[inline]
+	pub fn try_read(&self) -> Option<RwLockReadGuard<'_, T>> {
+		self.0.try_read()
+	}
+
+	#[inline]
+	pub fn try_write(&self) -> Option<RwLockWriteGuard<'_, T>> {
+		self.0.try_write()
+	}
+
+	#[inline]
+	pub fn try_read_for(&self, timeout: Duration) -> Option<RwLockReadGuard<'_, T>> {
+		self.0.try_read_for(timeout)
+	}
+
+	#[inline]
+	pub fn try_write_for(&self, timeout: Duration) -> Option<RwLockWriteGuard<'_, T>> {
+		self.0.try_write_for(timeout)
+	}
+
+	/// Like [read](Self::read), but the guard holds a reference to the [Arc] instead of borrowing this handle.
+	#[inline]
+	pub fn read_arc(&self) -> ArcRwLockReadGuard<RawRwLock, T> {
+		self.0.read_arc()
+	}
+
+	/// Like [write](Self::write), but the guard holds a reference to the [Arc] instead of borrowing this handle.
+	#[inline]
+	pub fn write_arc(&self) -> ArcRwLockWriteGuard<RawRwLock, T> {
+		self.0.write_arc()
+	}
+
+	#[inline]
+	pub fn try_read_arc_for(&self, timeout: Duration) -> Option<ArcRwLockReadGuard<RawRwLock, T>> {
+		self.0.try_read_arc_for(timeout)
+	}
+
+	#[inline]
+	pub fn try_write_arc_for(&self, timeout: Duration) -> Option<ArcRwLockWriteGuard<RawRwLock, T>> {
+		self.0.try_write_arc_for(timeout)
+	}
+
+	/// Read locks the value for the duration of `f`.
+	#[inline]
+	pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+		f(&self.0.read())
+	}
+
+	/// Write locks the value for the duration of `f`.
+	#[inline]
+	pub fn with_mut<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+		f(&mut self.0.write())
+	}
+
+	/// Returns `true` if both handles point to the same value.
+	#[inline]
+	pub fn ptr_eq(&self, other: &Self) -> bool {
+		Arc::ptr_eq(&self.0, &other.0)
+	}
+
+	#[inline]
+	pub fn as_arc(&self) -> &Arc<RwLock<T>> {
+		&self.0
+	}
+
+	#[inline]
+	pub fn into_arc(self) -> Arc<RwLock<T>> {
+		self.0
+	}
+}
+
+impl<T: ?Sized> Clone for SharedRw<T> {
+	#[inline]
+	fn clone(&self) -> Self {
+		Self(self.0.clone())
+	}
+}
+
+impl<T: Default> Default for SharedRw<T> {
+	#[inline]
+	fn default() -> Self {
+		Self::new(crate::default())
+	}
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for SharedRw<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("SharedRw").field(&self.0).finish()
+	}
+}
+
+impl<T> From<T> for SharedRw<T> {
+	#[inline]
+	fn from(value: T) -> Self {
+		Self::new(value)
+	}
+}
+
+impl<T: ?Sized> From<Arc<RwLock<T>>> for SharedRw<T> {
+	#[inline]
+	fn from(value: Arc<RwLock<T>>) -> Self {
+		Self(value)
+	}
+}
@@ -0,0 +1,489 @@
+use std::fmt;
+use std::time::Duration;
+
+use parking_lot::{Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+#[cfg(feature = "lock_tracing")]
+use std::ops::{Deref, DerefMut};
+#[cfg(feature = "lock_tracing")]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "lock_tracing")]
+use std::time::Instant;
+#[cfg(feature = "lock_tracing")]
+use once_cell::sync::OnceCell;
+
+/// A [Mutex] that records how long it's waited on and held for under its name, see [lock_report].
+///
+/// Recording only happens with the `lock_tracing` feature, without it this is just a [Mutex] and its guards are [MutexGuard]s.
+///
+/// # Examples
+/// ```
+/// use nil::prelude::*;
+/// use nil::sync::lock_report;
+///
+/// static ASSETS: TracedMutex<Vec<&str>> = TracedMutex::new("assets", Vec::new());
+///
+/// ASSETS.lock().push("player.png");
+/// assert_eq!(ASSETS.lock().len(), 1);
+///
+/// if cfg!(feature = "lock_tracing") {
+///     let report = lock_report();
+///     let assets = report.locks.iter().find(|lock| lock.name == "assets").unwrap();
+///     assert_eq!(assets.acquisitions, 2);
+///     println!("{report}");
+/// }
+/// ```
+pub struct TracedMutex<T: ?Sized> {
+	#[cfg(feature = "lock_tracing")]
+	stats: LockStatsCell,
+	inner: Mutex<T>,
+}
+
+/// A [RwLock] that records how long it's waited on and held for under its name, see [lock_report].
+///
+/// Recording only happens with the `lock_tracing` feature, without it this is just a [RwLock] and its guards are [RwLockReadGuard]s and [RwLockWriteGuard]s.
+pub struct TracedRwLock<T: ?Sized> {
+	#[cfg(feature = "lock_tracing")]
+	stats: LockStatsCell,
+	inner: RwLock<T>,
+}
+
+#[cfg(feature = "lock_tracing")]
+pub type TracedMutexGuard<'a, T> = TracedGuard<MutexGuard<'a, T>>;
+#[cfg(not(feature = "lock_tracing"))]
+pub type TracedMutexGuard<'a, T> = MutexGuard<'a, T>;
+
+#[cfg(feature = "lock_tracing")]
+pub type TracedRwLockReadGuard<'a, T> = TracedGuard<RwLockReadGuard<'a, T>>;
+#[cfg(not(feature = "lock_tracing"))]
+pub type TracedRwLockReadGuard<'a, T> = RwLockReadGuard<'a, T>;
+
+#[cfg(feature = "lock_tracing")]
+pub type TracedRwLockWriteGuard<'a, T> = TracedGuard<RwLockWriteGuard<'a, T>>;
+#[cfg(not(feature = "lock_tracing"))]
+pub type TracedRwLockWriteGuard<'a, T> = RwLockWriteGuard<'a, T>;
+
+/// Records how long a lock is held for when dropped.
+#[cfg(feature = "lock_tracing")]
+pub struct TracedGuard<G> {
+	guard: G,
+	stats: &'static LockStats,
+	acquired: Instant,
+}
+
+#[cfg(feature = "lock_tracing")]
+impl<G: Deref> Deref for TracedGuard<G> {
+	type Target = G::Target;
+	#[inline]
+	fn deref(&self) -> &Self::Target {
+		&self.guard
+	}
+}
+
+#[cfg(feature = "lock_tracing")]
+impl<G: DerefMut> DerefMut for TracedGuard<G> {
+	#[inline]
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.guard
+	}
+}
+
+#[cfg(feature = "lock_tracing")]
+impl<G> Drop for TracedGuard<G> {
+	fn drop(&mut self) {
+		self.stats.record_hold(self.acquired.elapsed());
+	}
+}
+
+#[cfg(feature = "lock_tracing")]
+impl<G: fmt::Debug> fmt::Debug for TracedGuard<G> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.guard.fmt(f)
+	}
+}
+
+/// Runs `try_lock`, falling back to `lock` and recording how long it waited if the lock was contended.
+#[cfg(feature = "lock_tracing")]
+#[inline]
+fn acquire<G>(stats: &'static LockStats, try_lock: impl FnOnce() -> Option<G>, lock: impl FnOnce() -> G) -> TracedGuard<G> {
+	let guard = match try_lock() {
+		Some(guard) => {
+			stats.record_acquire(None);
+			guard
+		}
+		None => {
+			let start = Instant::now();
+			let guard = lock();
+			stats.record_acquire(Some(start.elapsed()));
+			guard
+		}
+	};
+
+	TracedGuard { guard, stats, acquired: Instant::now() }
+}
+
+/// Like [acquire], but `lock` can time out, which is recorded as contention without an acquisition.
+#[cfg(feature = "lock_tracing")]
+#[inline]
+fn try_acquire<G>(stats: &'static LockStats, try_lock: impl FnOnce() -> Option<G>, lock: impl FnOnce() -> Option<G>) -> Option<TracedGuard<G>> {
+	let guard = match try_lock() {
+		Some(guard) => {
+			stats.record_acquire(None);
+			guard
+		}
+		None => {
+			let start = Instant::now();
+			let Some(guard) = lock() else {
+				stats.record_contention(start.elapsed());
+				return None;
+			};
+			stats.record_acquire(Some(start.elapsed()));
+			guard
+		}
+	};
+
+	Some(TracedGuard { guard, stats, acquired: Instant::now() })
+}
+
+macro_rules! traced {
+	($lock:expr, $try_lock:ident, $lock_fn:ident $(, $timeout:ident)?) => {{
+		#[cfg(feature = "lock_tracing")]
+		{
+			let stats = $lock.stats.get();
+			acquire(stats, || $lock.inner.$try_lock(), || $lock.inner.$lock_fn($($timeout)?))
+		}
+		#[cfg(not(feature = "lock_tracing"))]
+		{
+			$lock.inner.$lock_fn($($timeout)?)
+		}
+	}};
+}
+
+macro_rules! try_traced {
+	($lock:expr, $try_lock:ident, $lock_fn:ident $(, $timeout:ident)?) => {{
+		#[cfg(feature = "lock_tracing")]
+		{
+			let stats = $lock.stats.get();
+			try_acquire(stats, || $lock.inner.$try_lock(), || $lock.inner.$lock_fn($($timeout)?))
+		}
+		#[cfg(not(feature = "lock_tracing"))]
+		{
+			$lock.inner.$lock_fn($($timeout)?)
+		}
+	}};
+}
+
+impl<T> TracedMutex<T> {
+	/// Creates a new mutex recording its statistics under `name`. Locks with the same name share statistics.
+	#[inline]
+	pub const fn new(name: &'static str, value: T) -> Self {
+		#[cfg(not(feature = "lock_tracing"))]
+		let _ = name;
+		Self {
+			#[cfg(feature = "lock_tracing")]
+			stats: LockStatsCell::new(name),
+			inner: Mutex::new(value),
+		}
+	}
+
+	#[inline]
+	pub fn into_inner(self) -> T {
+		self.inner.into_inner()
+	}
+}
+
+impl<T: ?Sized> TracedMutex<T> {
+	#[inline]
+	pub fn lock(&self) -> TracedMutexGuard<'_, T> {
+		traced!(self, try_lock, lock)
+	}
+
+	#[inline]
+	pub fn try_lock(&self) -> Option<TracedMutexGuard<'_, T>> {
+		#[cfg(feature = "lock_tracing")]
+		{
+			let stats = self.stats.get();
+			try_acquire(stats, || self.inner.try_lock(), || None)
+		}
+		#[cfg(not(feature = "lock_tracing"))]
+		{
+			self.inner.try_lock()
+		}
+	}
+
+	#[inline]
+	pub fn try_lock_for(&self, timeout: Duration) -> Option<TracedMutexGuard<'_, T>> {
+		try_traced!(self, try_lock, try_lock_for, timeout)
+	}
+
+	#[inline]
+	pub fn is_locked(&self) -> bool {
+		self.inner.is_locked()
+	}
+
+	#[inline]
+	pub fn get_mut(&mut self) -> &mut T {
+		self.inner.get_mut()
+	}
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for TracedMutex<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.inner.fmt(f)
+	}
+}
+
+impl<T> TracedRwLock<T> {
+	/// Creates a new lock recording its statistics under `name`. Locks with the same name share statistics.
+	#[inline]
+	pub const fn new(name: &'static str, value: T) -> Self {
+		#[cfg(not(feature = "lock_tracing"))]
+		let _ = name;
+		Self {
+			#[cfg(feature = "lock_tracing")]
+			stats: LockStatsCell::new(name),
+			inner: RwLock::new(value),
+		}
+	}
+
+	#[inline]
+	pub fn into_inner(self) -> T {
+		self.inner.into_inner()
+	}
+}
+
+impl<T: ?Sized> TracedRwLock<T> {
+	#[inline]
+	pub fn read(&self) -> TracedRwLockReadGuard<'_, T> {
+		traced!(self, try_read, read)
+	}
+
+	#[inline]
+	pub fn write(&self) -> TracedRwLockWriteGuard<'_, T> {
+		traced!(self, try_write, write)
+	}
+
+	#[inline]
+	pub fn try_read(&self) -> Option<TracedRwLockReadGuard<'_, T>> {
+		#[cfg(feature = "lock_tracing")]
+		{
+			let stats = self.stats.get();
+			try_acquire(stats, || self.inner.try_read(), || None)
+		}
+		#[cfg(not(feature = "lock_tracing"))]
+		{
+			self.inner.try_read()
+		}
+	}
+
+	#[inline]
+	pub fn try_write(&self) -> Option<TracedRwLockWriteGuard<'_, T>> {
+		#[cfg(feature = "lock_tracing")]
+		{
+			let stats = self.stats.get();
+			try_acquire(stats, || self.inner.try_write(), || None)
+		}
+		#[cfg(not(feature = "lock_tracing"))]
+		{
+			self.inner.try_write()
+		}
+	}
+
+	#[inline]
+	pub fn try_read_for(&self, timeout: Duration) -> Option<TracedRwLockReadGuard<'_, T>> {
+		try_traced!(self, try_read, try_read_for, timeout)
+	}
+
+	#[inline]
+	pub fn try_write_for(&self, timeout: Duration) -> Option<TracedRwLockWriteGuard<'_, T>> {
+		try_traced!(self, try_write, try_write_for, timeout)
+	}
+
+	#[inline]
+	pub fn is_locked(&self) -> bool {
+		self.inner.is_locked()
+	}
+
+	#[inline]
+	pub fn get_mut(&mut self) -> &mut T {
+		self.inner.get_mut()
+	}
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for TracedRwLock<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.inner.fmt(f)
+	}
+}
+
+/// Looks up the statistics of a lock by name the first time it's used, so locks can be created in a `const` context.
+#[cfg(feature = "lock_tracing")]
+struct LockStatsCell {
+	name: &'static str,
+	stats: OnceCell<&'static LockStats>,
+}
+
+#[cfg(feature = "lock_tracing")]
+impl LockStatsCell {
+	const fn new(name: &'static str) -> Self {
+		Self { name, stats: OnceCell::new() }
+	}
+
+	#[inline]
+	fn get(&self) -> &'static LockStats {
+		self.stats.get_or_init(|| {
+			let mut registry = LOCK_STATS.lock();
+			if let Some(stats) = registry.iter().find(|stats| stats.name == self.name) {
+				return stats;
+			}
+			// Leaked since there's one per name, and it's needed for the rest of the program.
+			let stats = Box::leak(Box::new(LockStats::new(self.name)));
+			registry.push(stats);
+			stats
+		})
+	}
+}
+
+#[cfg(feature = "lock_tracing")]
+static LOCK_STATS: Mutex<Vec<&'static LockStats>> = Mutex::new(Vec::new());
+
+#[cfg(feature = "lock_tracing")]
+struct LockStats {
+	name: &'static str,
+	acquisitions: AtomicU64,
+	contentions: AtomicU64,
+	total_wait_nanos: AtomicU64,
+	max_wait_nanos: AtomicU64,
+	total_hold_nanos: AtomicU64,
+	max_hold_nanos: AtomicU64,
+}
+
+#[cfg(feature = "lock_tracing")]
+impl LockStats {
+	const fn new(name: &'static str) -> Self {
+		Self {
+			name,
+			acquisitions: AtomicU64::new(0),
+			contentions: AtomicU64::new(0),
+			total_wait_nanos: AtomicU64::new(0),
+			max_wait_nanos: AtomicU64::new(0),
+			total_hold_nanos: AtomicU64::new(0),
+			max_hold_nanos: AtomicU64::new(0),
+		}
+	}
+
+	/// `wait` is [Some] if the lock was contended.
+	fn record_acquire(&self, wait: Option<Duration>) {
+		self.acquisitions.fetch_add(1, Ordering::Relaxed);
+		if let Some(wait) = wait {
+			self.record_contention(wait);
+		}
+	}
+
+	fn record_contention(&self, wait: Duration) {
+		let nanos = wait.as_nanos() as u64;
+		self.contentions.fetch_add(1, Ordering::Relaxed);
+		self.total_wait_nanos.fetch_add(nanos, Ordering::Relaxed);
+		self.max_wait_nanos.fetch_max(nanos, Ordering::Relaxed);
+	}
+
+	fn record_hold(&self, hold: Duration) {
+		let nanos = hold.as_nanos() as u64;
+		self.total_hold_nanos.fetch_add(nanos, Ordering::Relaxed);
+		self.max_hold_nanos.fetch_max(nanos, Ordering::Relaxed);
+	}
+
+	fn snapshot(&self) -> LockSnapshot {
+		LockSnapshot {
+			name: self.name,
+			acquisitions: self.acquisitions.load(Ordering::Relaxed),
+			contentions: self.contentions.load(Ordering::Relaxed),
+			total_wait: Duration::from_nanos(self.total_wait_nanos.load(Ordering::Relaxed)),
+			max_wait: Duration::from_nanos(self.max_wait_nanos.load(Ordering::Relaxed)),
+			total_hold: Duration::from_nanos(self.total_hold_nanos.load(Ordering::Relaxed)),
+			max_hold: Duration::from_nanos(self.max_hold_nanos.load(Ordering::Relaxed)),
+		}
+	}
+
+	fn reset(&self) {
+		for counter in [
+			&self.acquisitions,
+			&self.contentions,
+			&self.total_wait_nanos,
+			&self.max_wait_nanos,
+			&self.total_hold_nanos,
+			&self.max_hold_nanos,
+		] {
+			counter.store(0, Ordering::Relaxed);
+		}
+	}
+}
+
+/// Statistics of every [TracedMutex] and [TracedRwLock] with the same name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockSnapshot {
+	pub name: &'static str,
+	pub acquisitions: u64,
+	/// How many times the lock was already locked when trying to acquire it.
+	pub contentions: u64,
+	pub total_wait: Duration,
+	pub max_wait: Duration,
+	pub total_hold: Duration,
+	pub max_hold: Duration,
+}
+
+/// Statistics of all traced locks at a point in time, sorted by total wait time. Displays as a table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LockReport {
+	pub locks: Vec<LockSnapshot>,
+}
+
+impl fmt::Display for LockReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name_width = self.locks.iter().map(|lock| lock.name.len()).max().unwrap_or(0).max(4);
+
+		write!(
+			f,
+			"{:<name_width$}  {:>12}  {:>11}  {:>12}  {:>12}  {:>12}  {:>12}",
+			"lock", "acquisitions", "contentions", "total wait", "max wait", "total hold", "max hold",
+		)?;
+
+		for lock in &self.locks {
+			write!(
+				f,
+				"\n{:<name_width$}  {:>12}  {:>11}  {:>12}  {:>12}  {:>12}  {:>12}",
+				lock.name,
+				lock.acquisitions,
+				lock.contentions,
+				format!("{:.2?}", lock.total_wait),
+				format!("{:.2?}", lock.max_wait),
+				format!("{:.2?}", lock.total_hold),
+				format!("{:.2?}", lock.max_hold),
+			)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Returns the statistics of every traced lock that has been used. Always empty without the `lock_tracing` feature.
+pub fn lock_report() -> LockReport {
+	#[cfg(feature = "lock_tracing")]
+	{
+		let mut locks: Vec<LockSnapshot> = LOCK_STATS.lock().iter().map(|stats| stats.snapshot()).collect();
+		locks.sort_by(|a, b| b.total_wait.cmp(&a.total_wait).then(a.name.cmp(b.name)));
+		LockReport { locks }
+	}
+	#[cfg(not(feature = "lock_tracing"))]
+	{
+		LockReport::default()
+	}
+}
+
+/// Resets the statistics of every traced lock.
+pub fn reset_lock_stats() {
+	#[cfg(feature = "lock_tracing")]
+	for stats in LOCK_STATS.lock().iter() {
+		stats.reset();
+	}
+}
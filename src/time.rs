@@ -0,0 +1,242 @@
+//! Timing helpers built on [Instant] and [Duration], and a global profile that [time_scope!](crate::time_scope) records to.
+//!
+//! # Examples
+//! ```
+//! use nil::prelude::*;
+//! use nil::time;
+//!
+//! fn load_level() -> usize {
+//!     time_scope!("load_level");
+//!     (0..1000).sum()
+//! }
+//!
+//! let (sum, elapsed) = timed!(load_level());
+//! assert_eq!(sum, 499500);
+//!
+//! let profile = time::profile();
+//! let entry = &profile.entries["load_level"];
+//! assert_eq!(entry.count, 1);
+//! assert!(entry.total <= elapsed);
+//!
+//! println!("{profile}");
+//! ```
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Measures elapsed time, and can be paused and resumed.
+///
+/// # Examples
+/// ```
+/// use nil::time::Stopwatch;
+/// use std::time::Duration;
+///
+/// let mut stopwatch = Stopwatch::new();
+/// std::thread::sleep(Duration::from_millis(5));
+/// stopwatch.pause();
+///
+/// let elapsed = stopwatch.elapsed();
+/// assert!(elapsed >= Duration::from_millis(5));
+/// std::thread::sleep(Duration::from_millis(5));
+/// assert_eq!(stopwatch.elapsed(), elapsed);
+///
+/// stopwatch.resume();
+/// assert!(stopwatch.is_running());
+/// assert!(stopwatch.restart() >= elapsed);
+/// assert!(stopwatch.elapsed() < elapsed);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stopwatch {
+	/// When the stopwatch was last resumed, [None] if it's paused.
+	resumed: Option<Instant>,
+	/// Time elapsed before the stopwatch was last resumed.
+	elapsed: Duration,
+}
+
+impl Stopwatch {
+	/// Creates a running stopwatch.
+	#[inline]
+	pub fn new() -> Self {
+		Self { resumed: Some(Instant::now()), elapsed: Duration::ZERO }
+	}
+
+	/// Creates a paused stopwatch at zero.
+	#[inline]
+	pub const fn new_paused() -> Self {
+		Self { resumed: None, elapsed: Duration::ZERO }
+	}
+
+	#[inline]
+	pub fn elapsed(&self) -> Duration {
+		match self.resumed {
+			Some(resumed) => self.elapsed + resumed.elapsed(),
+			None => self.elapsed,
+		}
+	}
+
+	#[inline]
+	pub fn is_running(&self) -> bool {
+		self.resumed.is_some()
+	}
+
+	/// Stops counting time until [resume](Self::resume) is called. Does nothing if already paused.
+	#[inline]
+	pub fn pause(&mut self) {
+		if let Some(resumed) = self.resumed.take() {
+			self.elapsed += resumed.elapsed();
+		}
+	}
+
+	/// Starts counting time again. Does nothing if already running.
+	#[inline]
+	pub fn resume(&mut self) {
+		if self.resumed.is_none() {
+			self.resumed = Some(Instant::now());
+		}
+	}
+
+	/// Sets the elapsed time back to zero, keeping it running or paused.
+	#[inline]
+	pub fn reset(&mut self) {
+		self.elapsed = Duration::ZERO;
+		if self.resumed.is_some() {
+			self.resumed = Some(Instant::now());
+		}
+	}
+
+	/// Resets and starts the stopwatch, returning the time elapsed before.
+	#[inline]
+	pub fn restart(&mut self) -> Duration {
+		let elapsed = self.elapsed();
+		*self = Self::new();
+		elapsed
+	}
+}
+
+impl Default for Stopwatch {
+	#[inline]
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Logs how long it was alive for to stderr when dropped, and records it to the global [profile]. Usually created through [time_scope!](crate::time_scope).
+#[derive(Debug)]
+pub struct ScopeTimer {
+	label: Cow<'static, str>,
+	start: Instant,
+}
+
+impl ScopeTimer {
+	#[inline]
+	pub fn new(label: impl Into<Cow<'static, str>>) -> Self {
+		Self { label: label.into(), start: Instant::now() }
+	}
+
+	#[inline]
+	pub fn label(&self) -> &str {
+		&self.label
+	}
+
+	#[inline]
+	pub fn elapsed(&self) -> Duration {
+		self.start.elapsed()
+	}
+}
+
+impl Drop for ScopeTimer {
+	fn drop(&mut self) {
+		let elapsed = self.start.elapsed();
+		eprintln!("{}: {elapsed:.2?}", self.label);
+		record(std::mem::take(&mut self.label), elapsed);
+	}
+}
+
+/// Timing statistics of a single label in a [Profile].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProfileEntry {
+	pub count: u64,
+	pub total: Duration,
+	pub min: Duration,
+	pub max: Duration,
+}
+
+impl ProfileEntry {
+	#[inline]
+	pub fn mean(&self) -> Duration {
+		if self.count == 0 {
+			return Duration::ZERO;
+		}
+		Duration::from_nanos((self.total.as_nanos() / self.count as u128) as u64)
+	}
+
+	fn add(&mut self, elapsed: Duration) {
+		self.count += 1;
+		self.total += elapsed;
+		self.min = self.min.min(elapsed);
+		self.max = self.max.max(elapsed);
+	}
+}
+
+/// Timing statistics per label. Displays as a table, sorted by total time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+	pub entries: HashMap<Cow<'static, str>, ProfileEntry>,
+}
+
+impl Profile {
+	/// Adds a measurement of `elapsed` to the entry of `label`.
+	pub fn record(&mut self, label: impl Into<Cow<'static, str>>, elapsed: Duration) {
+		self.entries
+			.entry(label.into())
+			.and_modify(|entry| entry.add(elapsed))
+			.or_insert(ProfileEntry { count: 1, total: elapsed, min: elapsed, max: elapsed });
+	}
+}
+
+impl fmt::Display for Profile {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut entries: Vec<_> = self.entries.iter().collect();
+		entries.sort_by(|(a_label, a), (b_label, b)| b.total.cmp(&a.total).then(a_label.cmp(b_label)));
+
+		let label_width = entries.iter().map(|(label, _)| label.len()).max().unwrap_or(0).max(5);
+
+		write!(f, "{:<label_width$}  {:>8}  {:>12}  {:>12}  {:>12}  {:>12}", "label", "count", "total", "min", "max", "mean")?;
+
+		for (label, entry) in entries {
+			write!(
+				f,
+				"\n{:<label_width$}  {:>8}  {:>12}  {:>12}  {:>12}  {:>12}",
+				label,
+				entry.count,
+				format!("{:.2?}", entry.total),
+				format!("{:.2?}", entry.min),
+				format!("{:.2?}", entry.max),
+				format!("{:.2?}", entry.mean()),
+			)?;
+		}
+
+		Ok(())
+	}
+}
+
+crate::global! {
+	static PROFILE: Mutex<Profile>;
+}
+
+/// Adds a measurement of `elapsed` to the global profile under `label`.
+pub fn record(label: impl Into<Cow<'static, str>>, elapsed: Duration) {
+	PROFILE.lock().record(label, elapsed);
+}
+
+/// Returns a copy of the global profile.
+pub fn profile() -> Profile {
+	PROFILE.lock().clone()
+}
+
+/// Clears the global profile.
+pub fn reset_profile() {
+	PROFILE.lock().entries.clear();
+}